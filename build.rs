@@ -0,0 +1,40 @@
+// Finds the src/dayNN.rs modules and generates the table of days from them,
+// so adding a day is just adding its file.
+use std::env;
+use std::fs;
+use std::path::Path;
+
+fn main() {
+    let src = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join("src");
+    println!("cargo:rerun-if-changed=src");
+
+    let mut days: Vec<u32> = fs::read_dir(&src)
+        .unwrap()
+        .filter_map(|entry| {
+            let name = entry.ok()?.file_name().into_string().ok()?;
+            let num = name.strip_prefix("day")?.strip_suffix(".rs")?;
+            if num.len() == 2 {
+                num.parse().ok()
+            } else {
+                None
+            }
+        })
+        .collect();
+    days.sort();
+
+    let mut out = String::new();
+    for day in &days {
+        let path = src.join(format!("day{:02}.rs", day));
+        out.push_str(&format!("#[path = {:?}]\npub mod day{:02};\n", path.display().to_string(), day));
+    }
+
+    out.push_str("\n// Every implemented day, in order. Each dayNN module provides a DayNN.\n");
+    out.push_str("pub static DAYS: &[&dyn DynSolution] = &[\n");
+    for day in &days {
+        out.push_str(&format!("    &day{:02}::Day{:02},\n", day, day));
+    }
+    out.push_str("];\n");
+
+    let dest = Path::new(&env::var("OUT_DIR").unwrap()).join("days.rs");
+    fs::write(dest, out).unwrap();
+}
@@ -0,0 +1,149 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::ops::{AddAssign, Div};
+use std::sync::atomic::{AtomicBool, AtomicIsize, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+// The system allocator, but counting allocations while enabled. The binary
+// installs it as the #[global_allocator]; until `enable()` it only costs a
+// load per call.
+pub struct CountingAlloc;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static COUNT: AtomicUsize = AtomicUsize::new(0);
+static BYTES: AtomicUsize = AtomicUsize::new(0);
+// Can go negative when freeing what was allocated before counting started.
+static LIVE: AtomicIsize = AtomicIsize::new(0);
+static PEAK: AtomicIsize = AtomicIsize::new(0);
+
+fn allocated(size: usize) {
+    COUNT.fetch_add(1, Ordering::Relaxed);
+    BYTES.fetch_add(size, Ordering::Relaxed);
+    let live = LIVE.fetch_add(size as isize, Ordering::Relaxed) + size as isize;
+    PEAK.fetch_max(live, Ordering::Relaxed);
+}
+
+fn freed(size: usize) {
+    LIVE.fetch_sub(size as isize, Ordering::Relaxed);
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if ENABLED.load(Ordering::Relaxed) {
+            allocated(layout.size());
+        }
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        if ENABLED.load(Ordering::Relaxed) {
+            allocated(layout.size());
+        }
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        if ENABLED.load(Ordering::Relaxed) {
+            freed(layout.size());
+        }
+        System.dealloc(ptr, layout)
+    }
+
+    // Counted as freeing the old block and allocating the new one.
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        if ENABLED.load(Ordering::Relaxed) {
+            freed(layout.size());
+            allocated(new_size);
+        }
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+// Starts counting. Only meaningful when `CountingAlloc` is the global allocator.
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+// Allocations made during some stretch of running.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AllocStats {
+    pub count: usize,
+    pub bytes: usize,
+    // Most bytes live at once, above what was live at the start.
+    pub peak: usize,
+}
+
+impl AddAssign for AllocStats {
+    fn add_assign(&mut self, rhs: Self) {
+        self.count += rhs.count;
+        self.bytes += rhs.bytes;
+        self.peak = self.peak.max(rhs.peak);
+    }
+}
+
+impl Div<u32> for AllocStats {
+    type Output = AllocStats;
+
+    // Averages the totals; the peak is already per run.
+    fn div(self, rhs: u32) -> Self::Output {
+        AllocStats {
+            count: self.count / rhs as usize,
+            bytes: self.bytes / rhs as usize,
+            peak: self.peak,
+        }
+    }
+}
+
+// Measures the time and allocations between laps. Counting is global, so
+// the allocations are only right when nothing else runs at the same time.
+pub struct Meter {
+    started: Instant,
+    count: usize,
+    bytes: usize,
+    live: isize,
+}
+
+impl Meter {
+    pub fn start() -> Self {
+        let live = LIVE.load(Ordering::Relaxed);
+        PEAK.store(live, Ordering::Relaxed);
+        Meter {
+            started: Instant::now(),
+            count: COUNT.load(Ordering::Relaxed),
+            bytes: BYTES.load(Ordering::Relaxed),
+            live,
+        }
+    }
+
+    // What happened since the last lap, and starts the next one.
+    pub fn lap(&mut self) -> (Duration, AllocStats) {
+        let elapsed = self.started.elapsed();
+        let stats = AllocStats {
+            count: COUNT.load(Ordering::Relaxed) - self.count,
+            bytes: BYTES.load(Ordering::Relaxed) - self.bytes,
+            peak: (PEAK.load(Ordering::Relaxed) - self.live).max(0) as usize,
+        };
+        *self = Meter::start();
+        (elapsed, stats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[global_allocator]
+    static ALLOC: CountingAlloc = CountingAlloc;
+
+    #[test]
+    fn counts_allocations() {
+        enable();
+        let mut meter = Meter::start();
+        let v = std::hint::black_box(vec![0u8; 10_000]);
+        drop(v);
+        let (_, stats) = meter.lap();
+        // Other tests may allocate at the same time, so these are lower bounds.
+        assert!(stats.count >= 1);
+        assert!(stats.bytes >= 10_000);
+        assert!(stats.peak >= 10_000);
+    }
+}
@@ -0,0 +1,114 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::solution::Answer;
+
+// Answers for the puzzle examples, which are the same for everyone.
+pub const EXAMPLE_USER: &str = "example";
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct DayAnswers {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    part1: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    part2: Option<String>,
+}
+
+impl DayAnswers {
+    fn part(&self, part: u32) -> &Option<String> {
+        match part {
+            1 => &self.part1,
+            2 => &self.part2,
+            _ => unreachable!(),
+        }
+    }
+
+    fn part_mut(&mut self, part: u32) -> &mut Option<String> {
+        match part {
+            1 => &mut self.part1,
+            2 => &mut self.part2,
+            _ => unreachable!(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Check {
+    Match,
+    Mismatch { expected: String },
+    Unknown,
+}
+
+// Known answers for one user, stored as `answers/<user>.toml`:
+//
+//   [day01]
+//   part1 = "70720"
+//   part2 = "207148"
+pub struct AnswerFile {
+    path: PathBuf,
+    days: BTreeMap<String, DayAnswers>,
+}
+
+fn day_key(day: u32) -> String {
+    format!("day{:02}", day)
+}
+
+impl AnswerFile {
+    pub fn path_for_user(user: &str) -> PathBuf {
+        PathBuf::from(format!("answers/{}.toml", user))
+    }
+
+    // A missing file is fine, and just means nothing is known yet.
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let days = if path.exists() {
+            toml::from_str(&std::fs::read_to_string(path)?)?
+        } else {
+            BTreeMap::new()
+        };
+        Ok(Self { path: path.to_owned(), days })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn expected(&self, day: u32, part: u32) -> Option<&str> {
+        self.days
+            .get(&day_key(day))
+            .and_then(|answers| answers.part(part).as_deref())
+    }
+
+    pub fn check(&self, day: u32, part: u32, answer: &Answer) -> Check {
+        match self.expected(day, part) {
+            None => Check::Unknown,
+            Some(expected) if expected == answer.to_string() => Check::Match,
+            Some(expected) => Check::Mismatch { expected: expected.to_string() },
+        }
+    }
+
+    // Records an answer, unless one is already known. Returns true if it was new.
+    pub fn record(&mut self, day: u32, part: u32, answer: &Answer) -> bool {
+        let slot = self.days.entry(day_key(day)).or_default().part_mut(part);
+        if slot.is_some() {
+            return false;
+        }
+        *slot = Some(answer.to_string());
+        true
+    }
+
+    // An empty entry for a day, to be filled in by hand or with --record.
+    pub fn add_day(&mut self, day: u32) {
+        self.days.entry(day_key(day)).or_default();
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(&self.path, toml::to_string_pretty(&self.days)?)?;
+        Ok(())
+    }
+}
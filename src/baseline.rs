@@ -0,0 +1,220 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
+
+use crate::bench::{fmt_micros, Stats};
+use crate::say;
+
+const HEADER: &str = "day,revision,samples,outliers,min_ns,median_ns,mean_ns,p95_ns,stddev_ns";
+
+// Regressions smaller than this many standard errors are treated as noise.
+const SIGNIFICANT_T: f64 = 3.0;
+
+// The --per results for each day, saved as CSV so they can be compared later.
+pub struct Baseline {
+    pub revision: String,
+    pub days: BTreeMap<u32, Stats>,
+}
+
+// The current git revision, marked "-dirty" when there are local changes.
+pub fn git_revision() -> String {
+    let run = |args: &[&str]| {
+        Command::new("git")
+            .args(args)
+            .output()
+            .ok()
+            .filter(|out| out.status.success())
+            .map(|out| String::from_utf8_lossy(&out.stdout).trim().to_string())
+    };
+    match run(&["rev-parse", "--short", "HEAD"]) {
+        Some(rev) if run(&["status", "--porcelain"]).is_some_and(|s| !s.is_empty()) => format!("{}-dirty", rev),
+        Some(rev) => rev,
+        None => "unknown".to_string(),
+    }
+}
+
+fn nanos(d: Duration) -> u128 {
+    d.as_nanos()
+}
+
+impl Default for Baseline {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Baseline {
+    pub fn new() -> Self {
+        Self { revision: git_revision(), days: BTreeMap::new() }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        let mut out = String::with_capacity(64 * (self.days.len() + 1));
+        out.push_str(HEADER);
+        out.push('\n');
+        for (day, stats) in &self.days {
+            out.push_str(&format!(
+                "{},{},{},{},{},{},{},{},{}\n",
+                day,
+                self.revision,
+                stats.samples,
+                stats.outliers,
+                nanos(stats.min),
+                nanos(stats.median),
+                nanos(stats.mean),
+                nanos(stats.p95),
+                nanos(stats.stddev),
+            ));
+        }
+        std::fs::write(path, out)?;
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let text = std::fs::read_to_string(path)?;
+        let mut lines = text.lines();
+        if lines.next() != Some(HEADER) {
+            return Err(format!("{} is not a baseline file", path.display()).into());
+        }
+
+        let mut baseline = Baseline { revision: "unknown".to_string(), days: BTreeMap::new() };
+        for line in lines {
+            let fields: Vec<&str> = line.split(',').collect();
+            if fields.len() != 9 {
+                return Err(format!("Bad baseline line: {}", line).into());
+            }
+            let ns = |i: usize| -> Result<Duration, Box<dyn Error>> {
+                Ok(Duration::from_nanos(fields[i].parse()?))
+            };
+            baseline.revision = fields[1].to_string();
+            baseline.days.insert(
+                fields[0].parse()?,
+                Stats {
+                    samples: fields[2].parse()?,
+                    outliers: fields[3].parse()?,
+                    min: ns(4)?,
+                    median: ns(5)?,
+                    mean: ns(6)?,
+                    p95: ns(7)?,
+                    stddev: ns(8)?,
+                },
+            );
+        }
+        Ok(baseline)
+    }
+
+    // Prints how each day changed since `old`. Returns the days that got slower
+    // by more than `threshold` (a fraction), beyond what noise explains.
+    pub fn compare(&self, old: &Baseline, threshold: f64) -> Vec<u32> {
+        say!("Compared to {}:", old.revision);
+        let mut regressed = Vec::new();
+        for (day, new) in &self.days {
+            let Some(old) = old.days.get(day) else {
+                say!("Day {:2} | {:>7} µs  (new)", day, fmt_micros(new.median));
+                continue;
+            };
+
+            let change = new.median.as_secs_f64() / old.median.as_secs_f64() - 1.0;
+
+            // Welch's t statistic for the difference in means.
+            let std_err = (old.stddev.as_secs_f64().powi(2) / old.samples as f64
+                + new.stddev.as_secs_f64().powi(2) / new.samples as f64)
+                .sqrt();
+            let t = (new.mean.as_secs_f64() - old.mean.as_secs_f64()) / std_err.max(1e-12);
+            let significant = t.abs() >= SIGNIFICANT_T;
+
+            let verdict = if !significant {
+                "noise"
+            } else if change > threshold {
+                regressed.push(*day);
+                "REGRESSED"
+            } else if change > 0.0 {
+                "slower"
+            } else {
+                "faster"
+            };
+            say!(
+                "Day {:2} | {:>7} -> {:>7} µs  {:>+6.1}%  {}",
+                day,
+                fmt_micros(old.median),
+                fmt_micros(new.median),
+                change * 100.0,
+                verdict,
+            );
+        }
+        regressed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Timings with the mean at the median, in microseconds.
+    fn stats(median: u64, stddev: u64, samples: usize) -> Stats {
+        Stats {
+            samples,
+            outliers: 0,
+            min: Duration::from_micros(median - stddev),
+            median: Duration::from_micros(median),
+            mean: Duration::from_micros(median),
+            p95: Duration::from_micros(median + 2 * stddev),
+            stddev: Duration::from_micros(stddev),
+        }
+    }
+
+    fn baseline(days: &[(u32, Stats)]) -> Baseline {
+        Baseline { revision: "abc1234".to_string(), days: days.iter().cloned().collect() }
+    }
+
+    #[test]
+    fn saves_and_loads() {
+        let path = std::env::temp_dir().join(format!("advent21-baseline-{}.csv", std::process::id()));
+        let saved = baseline(&[(1, stats(90, 4, 2000)), (16, stats(1_351_391, 20_000, 5))]);
+        saved.save(&path).unwrap();
+        let loaded = Baseline::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.revision, "abc1234");
+        assert_eq!(format!("{:?}", loaded.days), format!("{:?}", saved.days));
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        let path = std::env::temp_dir().join(format!("advent21-not-baseline-{}.csv", std::process::id()));
+        std::fs::write(&path, "day,answer\n1,42\n").unwrap();
+        let not_baseline = Baseline::load(&path).err().unwrap().to_string();
+        std::fs::write(&path, format!("{}\n1,abc1234,5,0,1,2,3\n", HEADER)).unwrap();
+        let short_line = Baseline::load(&path).err().unwrap().to_string();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(not_baseline.contains("is not a baseline file"));
+        assert!(short_line.starts_with("Bad baseline line"));
+    }
+
+    #[test]
+    fn only_significant_slowdowns_over_the_threshold_regress() {
+        let old = baseline(&[
+            (1, stats(100, 1, 100)),
+            (2, stats(100, 50, 5)),
+            (3, stats(100, 1, 100)),
+            (4, stats(100, 1, 100)),
+        ]);
+        let new = baseline(&[
+            // 50% slower, well beyond the noise.
+            (1, stats(150, 1, 100)),
+            // Just as much slower, but too noisy to tell.
+            (2, stats(150, 50, 5)),
+            // Clearly slower, but only by 5%.
+            (3, stats(105, 1, 100)),
+            // Faster.
+            (4, stats(80, 1, 100)),
+            // Not in the old baseline.
+            (5, stats(500, 1, 100)),
+        ]);
+        assert_eq!(new.compare(&old, 0.10), vec![1]);
+        assert_eq!(new.compare(&old, 0.01), vec![1, 3]);
+    }
+}
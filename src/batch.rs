@@ -0,0 +1,106 @@
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::answers::{AnswerFile, Check};
+use crate::runner::{parse_failure, run_day};
+use crate::say;
+use crate::solution::{DynSolution, Parts};
+use crate::utils::{tabulate, Grid, InputSource, ParseError};
+
+enum Outcome {
+    Missing,
+    Panicked,
+    Unparsable(ParseError),
+    Ran { check: Check, elapsed: Duration },
+}
+
+impl Outcome {
+    fn failed(&self) -> bool {
+        matches!(self, Outcome::Panicked | Outcome::Unparsable(_) | Outcome::Ran { check: Check::Mismatch { .. }, .. })
+    }
+
+    fn fmt_cell(&self) -> String {
+        match self {
+            Outcome::Missing => "-".to_string(),
+            Outcome::Panicked => "PANIC".to_string(),
+            Outcome::Unparsable(err) => format!("BAD INPUT {}:{}", err.line, err.column),
+            Outcome::Ran { check, elapsed } => {
+                let status = match check {
+                    Check::Match => "ok",
+                    Check::Mismatch { .. } => "FAIL",
+                    Check::Unknown => "?",
+                };
+                format!("{} {:.1?}", status, elapsed)
+            }
+        }
+    }
+}
+
+fn run_one(solution: &dyn DynSolution, input_dir: &Path, known: &AnswerFile) -> Outcome {
+    let source = InputSource::Dir(input_dir.to_owned());
+    if !Path::new(&source.describe(solution.day())).exists() {
+        return Outcome::Missing;
+    }
+
+    let started = Instant::now();
+    let result = panic::catch_unwind(AssertUnwindSafe(|| run_day(solution, &source, false, Parts::Both)));
+    let elapsed = started.elapsed();
+
+    let (part1, part2) = match result {
+        Ok(Ok((Some(part1), Some(part2)))) => (part1, part2),
+        Ok(Ok(_)) => unreachable!(),
+        Ok(Err(err)) => return Outcome::Unparsable(err),
+        Err(_) => return Outcome::Panicked,
+    };
+
+    // The worst of the two parts.
+    let check = match (known.check(solution.day(), 1, &part1), known.check(solution.day(), 2, &part2)) {
+        (mismatch @ Check::Mismatch { .. }, _) | (_, mismatch @ Check::Mismatch { .. }) => mismatch,
+        (Check::Unknown, _) | (_, Check::Unknown) => Check::Unknown,
+        _ => Check::Match,
+    };
+    Outcome::Ran { check, elapsed }
+}
+
+// Runs every day against every user's inputs under `root` (root/<user>/inputNN.txt),
+// checking each against answers/<user>.toml. Prints a day x user matrix and returns
+// false if anything failed.
+pub fn run_batch(days: &[&dyn DynSolution], root: &Path) -> bool {
+    let mut users: Vec<String> = std::fs::read_dir(root)
+        .unwrap_or_else(|err| panic!("Cannot read {}: {}", root.display(), err))
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .collect();
+    users.sort();
+
+    let mut table = Grid::filled(days.len() + 1, users.len() + 1, String::new());
+    let mut failures = 0;
+    let mut parse_failures = Vec::new();
+    for (c, user) in users.iter().enumerate() {
+        table[(0, c + 1)] = user.clone();
+        let known = AnswerFile::load(&AnswerFile::path_for_user(user)).unwrap();
+
+        for (r, solution) in days.iter().enumerate() {
+            table[(r + 1, 0)] = format!("Day {:2}", solution.day());
+
+            let input_dir = root.join(user);
+            let outcome = run_one(*solution, &input_dir, &known);
+            if outcome.failed() {
+                failures += 1;
+            }
+            table[(r + 1, c + 1)] = outcome.fmt_cell();
+            if let Outcome::Unparsable(err) = outcome {
+                parse_failures.push(parse_failure(&InputSource::Dir(input_dir), false, &err));
+            }
+        }
+    }
+
+    say!("{}", tabulate(&table).trim_end());
+    for failure in &parse_failures {
+        say!("{}", failure);
+    }
+    say!("{} users, {} failures", users.len(), failures);
+    failures == 0
+}
@@ -0,0 +1,158 @@
+use std::time::{Duration, Instant};
+
+use thousands::Separable;
+
+use crate::runner::Phases;
+
+// Each sample runs for at least this long, so the fast days are timed over
+// several iterations instead of being lost in timer noise.
+const MIN_SAMPLE_TIME: Duration = Duration::from_micros(500);
+
+pub struct BenchConfig {
+    pub warmup: Duration,
+    // Roughly how long to spend taking samples.
+    pub measure: Duration,
+    pub min_samples: usize,
+    pub max_samples: usize,
+}
+
+// Per-iteration timings, after dropping outliers.
+#[derive(Debug, Clone)]
+pub struct Stats {
+    pub samples: usize,
+    pub outliers: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub p95: Duration,
+    pub stddev: Duration,
+}
+
+// Nearest-rank percentile of sorted values.
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    let rank = (p * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+impl Stats {
+    // `times` are seconds per iteration for each sample.
+    pub fn from_samples(mut times: Vec<f64>) -> Self {
+        assert!(!times.is_empty());
+        times.sort_by(|a, b| a.total_cmp(b));
+
+        // Tukey's fences: anything more than 1.5 IQR outside the quartiles is an outlier.
+        let q1 = percentile(&times, 0.25);
+        let q3 = percentile(&times, 0.75);
+        let iqr = q3 - q1;
+        let (lo, hi) = (q1 - 1.5 * iqr, q3 + 1.5 * iqr);
+        let kept: Vec<f64> = times.iter().copied().filter(|t| (lo..=hi).contains(t)).collect();
+
+        let mean = kept.iter().sum::<f64>() / kept.len() as f64;
+        let variance = kept.iter().map(|t| (t - mean).powi(2)).sum::<f64>() / kept.len() as f64;
+
+        Stats {
+            samples: times.len(),
+            outliers: times.len() - kept.len(),
+            min: Duration::from_secs_f64(kept[0]),
+            median: Duration::from_secs_f64(percentile(&kept, 0.5)),
+            mean: Duration::from_secs_f64(mean),
+            p95: Duration::from_secs_f64(percentile(&kept, 0.95)),
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        }
+    }
+}
+
+// Median time of each phase, over the per-iteration averages of each sample.
+fn median_phases(samples: &[Phases]) -> Phases {
+    let median = |get: fn(&Phases) -> Duration| {
+        let mut times: Vec<Duration> = samples.iter().map(get).collect();
+        times.sort();
+        times[times.len() / 2]
+    };
+    Phases {
+        read: median(|p| p.read),
+        parse: median(|p| p.parse),
+        part1: median(|p| p.part1),
+        part2: median(|p| p.part2),
+        // The allocations don't vary, so any sample will do.
+        allocs: samples[0].allocs,
+    }
+}
+
+// Warms up, then picks how many samples fit in the measuring time. `f` runs
+// one iteration and reports how long its phases took.
+pub fn bench<F: FnMut() -> Phases>(config: &BenchConfig, mut f: F) -> (Stats, Phases) {
+    // Warmup, which also estimates how long an iteration takes.
+    let started = Instant::now();
+    let mut warmup_iters = 0u32;
+    while warmup_iters == 0 || started.elapsed() < config.warmup {
+        f();
+        warmup_iters += 1;
+    }
+    let estimate = (started.elapsed() / warmup_iters).as_secs_f64().max(1e-9);
+
+    let iters_per_sample = (MIN_SAMPLE_TIME.as_secs_f64() / estimate).ceil().max(1.0) as u32;
+    let samples = (config.measure.as_secs_f64() / (estimate * iters_per_sample as f64)) as usize;
+    let samples = samples.clamp(config.min_samples, config.max_samples);
+
+    let mut times = Vec::with_capacity(samples);
+    let mut phases = Vec::with_capacity(samples);
+    for _ in 0..samples {
+        let mut sample_phases = Phases::default();
+        let sample_started = Instant::now();
+        for _ in 0..iters_per_sample {
+            sample_phases += f();
+        }
+        times.push(sample_started.elapsed().as_secs_f64() / iters_per_sample as f64);
+        phases.push(sample_phases / iters_per_sample);
+    }
+
+    (Stats::from_samples(times), median_phases(&phases))
+}
+
+// Whole microseconds, except for the tiny times where that would just be 0 or 1.
+pub fn fmt_micros(d: Duration) -> String {
+    if d < Duration::from_micros(100) {
+        format!("{:.1}", d.as_secs_f64() * 1e6)
+    } else {
+        d.as_micros().separate_with_commas()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drops_outliers_before_the_stats() {
+        let mut times: Vec<f64> = (1..=10).map(|t| t as f64).collect();
+        times.push(100.0);
+        let stats = Stats::from_samples(times);
+
+        assert_eq!(stats.samples, 11);
+        assert_eq!(stats.outliers, 1);
+        assert_eq!(stats.min, Duration::from_secs(1));
+        assert_eq!(stats.median, Duration::from_secs(5));
+        assert_eq!(stats.mean, Duration::from_secs_f64(5.5));
+        assert_eq!(stats.p95, Duration::from_secs(10));
+        // The population standard deviation of 1..=10.
+        assert!((stats.stddev.as_secs_f64() - 8.25f64.sqrt()).abs() < 1e-6);
+    }
+
+    #[test]
+    fn one_sample() {
+        let stats = Stats::from_samples(vec![0.002]);
+        assert_eq!((stats.samples, stats.outliers), (1, 0));
+        assert_eq!(stats.min, stats.p95);
+        assert_eq!(stats.stddev, Duration::ZERO);
+    }
+
+    #[test]
+    fn percentiles_are_nearest_rank() {
+        let sorted = [1.0, 2.0, 3.0, 4.0];
+        assert_eq!(percentile(&sorted, 0.0), 1.0);
+        assert_eq!(percentile(&sorted, 0.5), 2.0);
+        assert_eq!(percentile(&sorted, 0.51), 3.0);
+        assert_eq!(percentile(&sorted, 1.0), 4.0);
+    }
+}
@@ -0,0 +1,107 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::path::Path;
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::bench::Stats;
+use crate::say;
+
+// What every day together should stay under.
+pub const GOAL: Duration = Duration::from_secs(1);
+
+// How long each day may take, in milliseconds of median time:
+//
+//   total = 1000
+//
+//   [days]
+//   day01 = 5
+//   day16 = 300
+//
+// Days without an allowance only count towards the total.
+#[derive(Debug, Deserialize)]
+struct BudgetFile {
+    total: Option<f64>,
+    #[serde(default)]
+    days: BTreeMap<String, f64>,
+}
+
+pub struct Budget {
+    pub total: Duration,
+    pub days: BTreeMap<u32, Duration>,
+}
+
+fn millis(ms: f64) -> Duration {
+    Duration::from_secs_f64(ms / 1000.0)
+}
+
+fn fmt_millis(d: Duration) -> String {
+    format!("{:.3}", d.as_secs_f64() * 1000.0)
+}
+
+impl Budget {
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let file: BudgetFile = toml::from_str(&std::fs::read_to_string(path)?)?;
+        let mut days = BTreeMap::new();
+        for (key, ms) in file.days {
+            let day = key
+                .strip_prefix("day")
+                .and_then(|day| day.parse().ok())
+                .ok_or_else(|| format!("{:?} should be named like \"day07\"", key))?;
+            days.insert(day, millis(ms));
+        }
+        Ok(Budget { total: file.total.map_or(GOAL, millis), days })
+    }
+
+    // Prints each day's median against its allowance, along with its share of
+    // the 1 s goal. Returns how many days (and the total) went over.
+    pub fn check(&self, results: &BTreeMap<u32, Stats>) -> usize {
+        let mut over = 0;
+        let mut row = |label: String, median: Duration, allowed: Option<Duration>| {
+            let status = match allowed {
+                None => "",
+                Some(allowed) if median > allowed => {
+                    over += 1;
+                    "OVER BUDGET"
+                }
+                Some(_) => "ok",
+            };
+            let line = format!(
+                "{:6} | {:>10} | {:>10} | {:>6.2}% | {}",
+                label,
+                fmt_millis(median),
+                allowed.map_or("-".to_string(), fmt_millis),
+                100.0 * median.as_secs_f64() / GOAL.as_secs_f64(),
+                status,
+            );
+            say!("{}", line.trim_end());
+        };
+
+        say!("Budget | {:>10} | {:>10} | {:>7} | (ms)", "median", "allowed", "of 1 s");
+        let mut total = Duration::ZERO;
+        for (day, stats) in results {
+            total += stats.median;
+            row(format!("Day {:2}", day), stats.median, self.days.get(day).copied());
+        }
+        row("Total".to_string(), total, Some(self.total));
+        over
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loads_allowances() {
+        let path = std::env::temp_dir().join(format!("budget-{}.toml", std::process::id()));
+        std::fs::write(&path, "[days]\nday01 = 5\nday16 = 0.5\n").unwrap();
+        let budget = Budget::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(budget.total, GOAL);
+        assert_eq!(budget.days[&1], Duration::from_millis(5));
+        assert_eq!(budget.days[&16], Duration::from_micros(500));
+    }
+}
@@ -1,51 +1,87 @@
 use std::cmp::max;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-
-const DAY: i32 = 1;
-
-pub fn day01(_test_mode: bool, print: bool) {
-    let reader = BufReader::new(File::open("inputs/input01.txt").expect("Cannot open input file"));
-
-    let mut carrying = Vec::<Vec<i32>>::with_capacity(100);
-    carrying.push(Vec::new());
-    for line in reader.lines() {
-        let text = line.unwrap();
-        if text.is_empty() {
-            carrying.push(Vec::new());
-        } else {
-            carrying.last_mut().unwrap().push(text.parse().unwrap());
+
+use crate::solution::{Answer, Solution};
+use crate::utils::{lines, ParseError};
+
+pub struct Day01;
+
+impl Solution for Day01 {
+    const DAY: u32 = 1;
+    const EXAMPLE: &'static str = TEST_EXAMPLE;
+
+    type Parsed = Vec<Vec<i32>>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        let mut carrying = Vec::<Vec<i32>>::with_capacity(100);
+        carrying.push(Vec::new());
+        for mut line in lines(input) {
+            if line.at_end() {
+                carrying.push(Vec::new());
+            } else {
+                carrying.last_mut().unwrap().push(line.integer()?);
+                line.end()?;
+            }
         }
+        Ok(carrying)
     }
 
-    let mut part1 = 0;
-    let mut top3 = (0i32, 0i32, 0i32);
-
-    for elf in &carrying {
-        let this_carry = elf.iter().sum();
-        part1 = max(part1, this_carry);
-
-        if this_carry > top3.0 {
-            top3.2 = top3.1;
-            top3.1 = top3.0;
-            top3.0 = this_carry;
-        } else if this_carry > top3.1 {
-            top3.2 = top3.1;
-            top3.1 = this_carry;
-        } else if this_carry > top3.2 {
-            top3.2 = this_carry;
+    fn part1(carrying: &Self::Parsed) -> Answer {
+        let mut part1 = 0;
+        for elf in carrying {
+            part1 = max(part1, elf.iter().sum());
         }
+        part1.into()
     }
 
-    if print {
-        println!("Day {}.  Part 1: {}", DAY, part1);
+    fn part2(carrying: &Self::Parsed) -> Answer {
+        let mut top3 = (0i32, 0i32, 0i32);
+
+        for elf in carrying {
+            let this_carry = elf.iter().sum();
+
+            if this_carry > top3.0 {
+                top3.2 = top3.1;
+                top3.1 = top3.0;
+                top3.0 = this_carry;
+            } else if this_carry > top3.1 {
+                top3.2 = top3.1;
+                top3.1 = this_carry;
+            } else if this_carry > top3.2 {
+                top3.2 = this_carry;
+            }
+        }
+
+        (top3.0 + top3.1 + top3.2).into()
     }
-    assert_eq!(part1, 70720);
+}
+
+const TEST_EXAMPLE: &str = "1000
+2000
+3000
+
+4000
+
+5000
+6000
 
-    let part2 = top3.0 + top3.1 + top3.2;
+7000
+8000
+9000
+
+10000";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::testing::{part1, part2};
+
+    #[test]
+    fn example_part1() {
+        assert_eq!(part1::<Day01>(TEST_EXAMPLE), 24000.into());
+    }
 
-    if print {
-        println!("Day {}.  Part 2: {}", DAY, part2);
+    #[test]
+    fn example_part2() {
+        assert_eq!(part2::<Day01>(TEST_EXAMPLE), 45000.into());
     }
-    assert_eq!(part2, 207148);
 }
@@ -1,16 +1,9 @@
-const DAY: i32 = 2;
+use crate::solution::{Answer, Solution};
+use crate::utils::{lines, lint_lines, Line, ParseError};
 
-fn to_num(s: &str) -> i32 {
-    match s {
-        "A" => 0, // Rock
-        "B" => 1, // Paper
-        "C" => 2, // Scissors
-        "X" => 0,
-        "Y" => 1,
-        "Z" => 2,
-        _ => unreachable!(),
-    }
-}
+// Rock, Paper, Scissors
+const THEM: [(&str, i32); 3] = [("A", 0), ("B", 1), ("C", 2)];
+const US: [(&str, i32); 3] = [("X", 0), ("Y", 1), ("Z", 2)];
 
 fn win_score(them: i32, us: i32) -> i32 {
     if them == us {
@@ -22,48 +15,72 @@ fn win_score(them: i32, us: i32) -> i32 {
     }
 }
 
-pub fn day02(test_mode: bool, print: bool) {
-    let file_str = std::fs::read_to_string(format!("inputs/input{:02}.txt", DAY)).unwrap();
-    let input_str = if test_mode {
-        TEST_EXAMPLE
-    } else {
-        &file_str.trim_end()
-    };
+// "A Y"
+fn parse_round(mut line: Line) -> Result<(i32, i32), ParseError> {
+    let them = line.one_of(&THEM)?;
+    line.literal(" ")?;
+    let us = line.one_of(&US)?;
+    line.end()?;
+    Ok((them, us))
+}
 
-    let lines = input_str.lines();
-    let input: Vec<_> = lines
-        .map(|line| {
-            let mut parts = line.split(" ");
-            let them = to_num(parts.next().unwrap());
-            let us = to_num(parts.next().unwrap());
-            (them, us)
-        })
-        .collect();
+pub struct Day02;
 
-    let mut part1 = 0;
-    for round in &input {
-        part1 += win_score(round.0, round.1) + round.1 + 1;
-    }
+impl Solution for Day02 {
+    const DAY: u32 = 2;
+    const EXAMPLE: &'static str = TEST_EXAMPLE;
 
-    let mut part2 = 0;
-    for (them, goal) in &input {
-        let us = (them + goal - 1 + 3) % 3;
-        part2 += win_score(*them, us) + us + 1;
-    }
+    type Parsed = Vec<(i32, i32)>;
 
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        lines(input).map(parse_round).collect()
+    }
 
-    if print {
-        println!("Day {}.  Part 1: {}", DAY, part1);
+    fn lint(input: &str) -> Vec<ParseError> {
+        lint_lines(input, parse_round)
     }
-    assert_eq!(part1, if test_mode { 15 } else { 10624 });
 
+    fn part1(input: &Self::Parsed) -> Answer {
+        let mut part1 = 0;
+        for round in input {
+            part1 += win_score(round.0, round.1) + round.1 + 1;
+        }
+        part1.into()
+    }
 
-    if print {
-        println!("Day {}.  Part 2: {}", DAY, part2);
+    fn part2(input: &Self::Parsed) -> Answer {
+        let mut part2 = 0;
+        for (them, goal) in input {
+            let us = (them + goal - 1 + 3) % 3;
+            part2 += win_score(*them, us) + us + 1;
+        }
+        part2.into()
     }
-    assert_eq!(part2, if test_mode { 12 } else { 14060 });
 }
 
-const TEST_EXAMPLE: &'static str = "A Y
+const TEST_EXAMPLE: &str = "A Y
 B X
 C Z";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::testing::{part1, part2};
+
+    #[test]
+    fn example_part1() {
+        assert_eq!(part1::<Day02>(TEST_EXAMPLE), 15.into());
+    }
+
+    #[test]
+    fn example_part2() {
+        assert_eq!(part2::<Day02>(TEST_EXAMPLE), 12.into());
+    }
+
+    #[test]
+    fn bad_move() {
+        let err = Day02::parse("A Y\nB Q").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 3));
+        assert_eq!(err.expected, "one of \"X\", \"Y\", \"Z\"");
+    }
+}
@@ -1,4 +1,5 @@
-const DAY: i32 = 3;
+use crate::solution::{Answer, Solution};
+use crate::utils::{lines, lint_lines, Line, ParseError};
 
 pub fn letter_to_val(letter: char) -> u8 {
     match letter {
@@ -8,68 +9,91 @@ pub fn letter_to_val(letter: char) -> u8 {
     }
 }
 
-pub fn day03(test_mode: bool, print: bool) {
-    let file_str = std::fs::read_to_string(format!("inputs/input{:02}.txt", DAY)).unwrap();
-    let input_str = if test_mode {
-        TEST_EXAMPLE
-    } else {
-        &file_str.trim_end()
-    };
-
-    let input: Vec<Vec<u8>> = input_str.lines().map(|line| {
-        line.chars().map(letter_to_val).collect()
-    }).collect();
-
-    let mut part1 = 0;
-    for bag in &input {
-        let mut have = [0u8; 53];
-        let mid = bag.len() / 2;
-
-        for i in 0..mid {
-            have[bag[i] as usize] = 1;
-        }
+fn parse_bag(mut line: Line) -> Result<Vec<u8>, ParseError> {
+    let mut bag = Vec::with_capacity(line.rest().len());
+    while !line.at_end() {
+        let letter = line.char_where("an item letter", |ch| ch.is_ascii_alphabetic())?;
+        bag.push(letter_to_val(letter));
+    }
+    Ok(bag)
+}
 
-        for i in mid..bag.len() {
-            let item = bag[i];
-            if have[item as usize] == 1 {
-                part1 += item as i32;
-                break;
-            }
-        }
+pub struct Day03;
+
+impl Solution for Day03 {
+    const DAY: u32 = 3;
+    const EXAMPLE: &'static str = TEST_EXAMPLE;
+
+    type Parsed = Vec<Vec<u8>>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        lines(input).map(parse_bag).collect()
     }
 
-    let mut part2 = 0;
-    for chunk in input.chunks(3) {
-        let mut have = [0u8; 53];
+    fn lint(input: &str) -> Vec<ParseError> {
+        lint_lines(input, parse_bag)
+    }
 
-        for (i, bag) in chunk.iter().enumerate() {
-            for item in bag {
-                have[*item as usize] = have[*item as usize] | (1 << i);
+    fn part1(input: &Self::Parsed) -> Answer {
+        let mut part1 = 0;
+        for bag in input {
+            let mut have = [0u8; 53];
+            let mid = bag.len() / 2;
+
+            for item in &bag[..mid] {
+                have[*item as usize] = 1;
             }
-        }
 
-        for (i, k) in have.iter().enumerate() {
-            if *k == 7 {
-                part2 += i as i32;
+            for item in &bag[mid..] {
+                if have[*item as usize] == 1 {
+                    part1 += *item as i32;
+                    break;
+                }
             }
         }
+        part1.into()
     }
 
-    if print {
-        println!("Day {}.  Part 1: {}", DAY, part1);
-    }
-    assert_eq!(part1, if test_mode { 157 } else { 7766 });
+    fn part2(input: &Self::Parsed) -> Answer {
+        let mut part2 = 0;
+        for chunk in input.chunks(3) {
+            let mut have = [0u8; 53];
 
+            for (i, bag) in chunk.iter().enumerate() {
+                for item in bag {
+                    have[*item as usize] |= 1 << i;
+                }
+            }
 
-    if print {
-        println!("Day {}.  Part 2: {}", DAY, part2);
+            for (i, k) in have.iter().enumerate() {
+                if *k == 7 {
+                    part2 += i as i32;
+                }
+            }
+        }
+        part2.into()
     }
-    assert_eq!(part2, if test_mode { 70 } else { 2415 });
 }
 
-const TEST_EXAMPLE: &'static str = "vJrwpWtwJgWrhcsFMMfFFhFp
+const TEST_EXAMPLE: &str = "vJrwpWtwJgWrhcsFMMfFFhFp
 jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
 PmmdzqPrVvPwwTWBwg
 wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
 ttgJtRGJQctTZtZT
 CrZsJsPPZsGzwwsLwLmpwMDw";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::testing::{part1, part2};
+
+    #[test]
+    fn example_part1() {
+        assert_eq!(part1::<Day03>(TEST_EXAMPLE), 157.into());
+    }
+
+    #[test]
+    fn example_part2() {
+        assert_eq!(part2::<Day03>(TEST_EXAMPLE), 70.into());
+    }
+}
@@ -1,51 +1,81 @@
-const DAY: i32 = 4;
+use crate::solution::{Answer, Solution};
+use crate::utils::{lines, lint_lines, Line, ParseError};
+
+// Inclusive.
+type Range = (i32, i32);
 
 // 34-96  -->  (34, 96)
-fn parse_range(input: &str) -> (i32, i32) {
-    let (a, b) = input.split_once("-").unwrap();
-    (a.parse().unwrap(), b.parse().unwrap())
+fn parse_range(line: &mut Line) -> Result<Range, ParseError> {
+    let a = line.integer()?;
+    line.literal("-")?;
+    Ok((a, line.integer()?))
 }
 
-pub fn day04(test_mode: bool, print: bool) {
-    let file_str = std::fs::read_to_string(format!("inputs/input{:02}.txt", DAY)).unwrap();
-    let input_str = if test_mode {
-        TEST_EXAMPLE
-    } else {
-        &file_str.trim_end()
-    };
-
-    let input: Vec<_> = input_str.lines().map(|line| {
-        let (r1, r2) = line.split_once(",").unwrap();
-        (parse_range(r1), parse_range(r2))
-    }).collect();
-
-    let mut part1 = 0;
-    let mut part2 = 0;
-    for (elf1, elf2) in input {
-        if (elf1.0 <= elf2.0 && elf1.1 >= elf2.1) || (elf2.0 <= elf1.0 && elf2.1 >= elf1.1) {
-            part1 += 1;
-        }
+// 2-4,6-8
+fn parse_pair(mut line: Line) -> Result<(Range, Range), ParseError> {
+    let r1 = parse_range(&mut line)?;
+    line.literal(",")?;
+    let r2 = parse_range(&mut line)?;
+    line.end()?;
+    Ok((r1, r2))
+}
 
-        if !(elf1.1 < elf2.0 || elf1.0 > elf2.1) {
-            part2 += 1;
-        }
+pub struct Day04;
+
+impl Solution for Day04 {
+    const DAY: u32 = 4;
+    const EXAMPLE: &'static str = TEST_EXAMPLE;
+
+    type Parsed = Vec<(Range, Range)>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        lines(input).map(parse_pair).collect()
     }
 
-    if print {
-        println!("Day {}.  Part 1: {}", DAY, part1);
+    fn lint(input: &str) -> Vec<ParseError> {
+        lint_lines(input, parse_pair)
     }
-    assert_eq!(part1, if test_mode { 2 } else { 503 });
 
+    fn part1(input: &Self::Parsed) -> Answer {
+        let mut part1 = 0;
+        for (elf1, elf2) in input {
+            if (elf1.0 <= elf2.0 && elf1.1 >= elf2.1) || (elf2.0 <= elf1.0 && elf2.1 >= elf1.1) {
+                part1 += 1;
+            }
+        }
+        part1.into()
+    }
 
-    if print {
-        println!("Day {}.  Part 2: {}", DAY, part2);
+    fn part2(input: &Self::Parsed) -> Answer {
+        let mut part2 = 0;
+        for (elf1, elf2) in input {
+            if !(elf1.1 < elf2.0 || elf1.0 > elf2.1) {
+                part2 += 1;
+            }
+        }
+        part2.into()
     }
-    assert_eq!(part2, if test_mode { 4 } else { 827 });
 }
 
-const TEST_EXAMPLE: &'static str = "2-4,6-8
+const TEST_EXAMPLE: &str = "2-4,6-8
 2-3,4-5
 5-7,7-9
 2-8,3-7
 6-6,4-6
 2-6,4-8";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::testing::{part1, part2};
+
+    #[test]
+    fn example_part1() {
+        assert_eq!(part1::<Day04>(TEST_EXAMPLE), 2.into());
+    }
+
+    #[test]
+    fn example_part2() {
+        assert_eq!(part2::<Day04>(TEST_EXAMPLE), 4.into());
+    }
+}
@@ -1,118 +1,211 @@
-const DAY: i32 = 5;
+use crate::solution::{Answer, Solution};
+use crate::utils::{lines, Line, ParseError};
 
 
 #[allow(dead_code)]
-fn show_stacks(stacks: &Vec<Vec<u8>>) {
+fn show_stacks(stacks: &[Vec<u8>]) {
     for stack in stacks {
         println!("{}", String::from_utf8_lossy(stack));
     }
 }
 
-fn do_move(stacks: &mut Vec<Vec<u8>>, src: i32, dst: i32) {
+fn do_move(stacks: &mut [Vec<u8>], src: i32, dst: i32) {
     let k = stacks[(src - 1) as usize].pop().unwrap();
     stacks[(dst - 1) as usize].push(k);
 }
 
 #[derive(Debug)]
-struct Action {
-    num: i32,
+pub struct Action {
+    num: usize,
     src: i32,
     dst: i32,
 }
 
-pub fn day05(test_mode: bool, print: bool) {
-    let file_str = std::fs::read_to_string(format!("inputs/input{:02}.txt", DAY)).unwrap();
-    let input_str = if test_mode {
-        TEST_EXAMPLE
-    } else {
-        &file_str.trim_end()
-    };
+// " 1   2   3 " under the drawing, which also covers any stacks that start empty.
+fn parse_stack_numbers(line: &mut Line, stacks: &mut Vec<Vec<u8>>) -> Result<(), ParseError> {
+    let mut count = 0;
+    while !line.at_end() {
+        if count > 0 {
+            line.literal(" ")?;
+        }
+        line.literal(" ")?;
+        let at = line.clone();
+        if line.integer::<usize>()? != count + 1 {
+            return Err(at.error(format!("stack number {}", count + 1)));
+        }
+        if !line.at_end() {
+            line.literal(" ")?;
+        }
+        count += 1;
+    }
+    if stacks.len() > count {
+        return Err(line.error(format!("stack number {}", count + 1)));
+    }
+    stacks.resize(count, Vec::with_capacity(16));
+    Ok(())
+}
+
+// "[X]" or "   " for each stack, with a space between. Editors like to trim
+// the trailing spaces, so a row can stop early.
+fn parse_crate_row(line: &mut Line, stacks: &mut Vec<Vec<u8>>) -> Result<(), ParseError> {
+    let mut i = 0;
+    while !line.at_end() {
+        if i > 0 {
+            line.literal(" ")?;
+        }
+        if line.try_literal("[") {
+            let container = line.char_where("a crate letter", |ch| ch.is_ascii_uppercase())?;
+            line.literal("]")?;
+            if stacks.len() <= i {
+                stacks.resize(i + 1, Vec::with_capacity(16));
+            }
+            stacks[i].push(container as u8);
+        } else if !line.try_literal("   ") {
+            return Err(line.error("a crate like \"[A]\", or three spaces"));
+        }
+        i += 1;
+    }
+    Ok(())
+}
+
+// The drawing ends with the line numbering the stacks.
+fn is_stack_numbers(line: &Line) -> bool {
+    line.rest().trim_start().starts_with(|ch: char| ch.is_ascii_digit())
+}
+
+// A 1-based stack number, which has to be one in the drawing.
+fn parse_stack(line: &mut Line, stack_count: usize) -> Result<i32, ParseError> {
+    let at = line.clone();
+    let stack = line.integer()?;
+    if !(1..=stack_count as i32).contains(&stack) {
+        return Err(at.error(format!("a stack from 1 to {}", stack_count)));
+    }
+    Ok(stack)
+}
+
+// "move 1 from 2 to 1", which can't move more crates than the stack has.
+// `heights` is how tall each stack is before the move, and after it once it's
+// checked.
+fn parse_action(mut line: Line, heights: &mut [usize]) -> Result<Action, ParseError> {
+    line.literal("move ")?;
+    let at = line.clone();
+    let num = line.integer()?;
+    line.literal(" from ")?;
+    let src = parse_stack(&mut line, heights.len())?;
+    line.literal(" to ")?;
+    let dst = parse_stack(&mut line, heights.len())?;
+    line.end()?;
+    let height = heights[src as usize - 1];
+    if num > height {
+        return Err(at.error(format!("at most {} crates, as stack {} has", height, src)));
+    }
+    heights[src as usize - 1] -= num;
+    heights[dst as usize - 1] += num;
+    Ok(Action { num, src, dst })
+}
+
+pub struct Day05;
+
+impl Solution for Day05 {
+    const DAY: u32 = 5;
+    const EXAMPLE: &'static str = TEST_EXAMPLE;
+
+    type Parsed = (Vec<Vec<u8>>, Vec<Action>);
 
-    let (stacks_input, moves) = {
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
         let mut stacks = Vec::<Vec<u8>>::with_capacity(10);
-        let mut moves = Vec::with_capacity(64);
-
-        let mut parsing_containers = true;
-        for line_str in input_str.lines() {
-            let line = line_str.as_bytes();
-            if parsing_containers {
-                if line.is_empty() {
-                    parsing_containers = false;
-                } else if line[1] == b'1' {
-                } else {
-                    if stacks.is_empty() {
-                        stacks.resize((line.len() + 1) / 4, Vec::with_capacity(16));
-                    }
-
-                    for (i, stack) in stacks.iter_mut().enumerate() {
-                        let container = line[4 * i + 1];
-                        if container != b' ' {
-                            stack.push(container);
-                        }
-                    }
-                }
-            } else {
-                let mut pieces = line_str.split(" ");
-                pieces.next().unwrap(); // move
-                let num = pieces.next().unwrap().parse().unwrap();
-                pieces.next().unwrap(); // from
-                let src = pieces.next().unwrap().parse().unwrap();
-                pieces.next().unwrap(); // to
-                let dst = pieces.next().unwrap().parse().unwrap();
-
-                moves.push(Action { num, src, dst });
+        let mut lines = lines(input);
+
+        // The drawing, top down, until the line numbering the stacks.
+        loop {
+            let mut line = lines.next_line("the stacks of crates")?;
+            if is_stack_numbers(&line) {
+                parse_stack_numbers(&mut line, &mut stacks)?;
+                break;
             }
+            parse_crate_row(&mut line, &mut stacks)?;
         }
+        lines.next_line("a blank line")?.end()?;
+
+        let mut heights: Vec<usize> = stacks.iter().map(Vec::len).collect();
+        let moves = lines
+            .map(|line| parse_action(line, &mut heights))
+            .collect::<Result<Vec<_>, _>>()?;
 
         for stack in stacks.iter_mut() {
             stack.reverse();
         }
-        (stacks, moves)
-    };
-
-    // println!("Parsed stacks:");
-    // show_stacks(&stacks_input);
-    // println!("\n");
+        Ok((stacks, moves))
+    }
 
-    let mut stacks = stacks_input.clone();
-    for action in &moves {
-        for _ in 0..action.num {
-            do_move(&mut stacks, action.src, action.dst);
+    fn lint(input: &str) -> Vec<ParseError> {
+        let mut errors = Vec::new();
+        let mut stacks = Vec::new();
+        let mut lines = lines(input);
+        loop {
+            let mut line = match lines.next_line("the stacks of crates") {
+                Ok(line) => line,
+                Err(err) => {
+                    errors.push(err);
+                    return errors;
+                }
+            };
+            let numbers = is_stack_numbers(&line);
+            let row = if numbers {
+                parse_stack_numbers(&mut line, &mut stacks)
+            } else {
+                parse_crate_row(&mut line, &mut stacks)
+            };
+            errors.extend(row.err());
+            if numbers {
+                break;
+            }
         }
+        errors.extend(lines.next_line("a blank line").and_then(|line| line.end()).err());
+        let mut heights: Vec<usize> = stacks.iter().map(Vec::len).collect();
+        errors.extend(lines.filter_map(|line| parse_action(line, &mut heights).err()));
+        errors
     }
 
-    let part1: String = stacks
-        .iter()
-        .map(|stack| *stack.last().unwrap() as char)
-        .collect();
+    fn part1((stacks_input, moves): &Self::Parsed) -> Answer {
+        // println!("Parsed stacks:");
+        // show_stacks(&stacks_input);
+        // println!("\n");
 
-    let mut stacks = stacks_input.clone();
-    for action in &moves {
-        let idx = stacks[action.src as usize - 1].len() - action.num as usize;
+        let mut stacks = stacks_input.clone();
+        for action in moves {
+            for _ in 0..action.num {
+                do_move(&mut stacks, action.src, action.dst);
+            }
+        }
 
-        // Unpleasant copy, since the borrow checker is challenging here.
-        let moving = stacks[action.src as usize - 1][idx..].to_owned();
-        stacks[action.dst as usize - 1].extend_from_slice(&moving);
-        stacks[action.src as usize - 1].truncate(idx);
+        let part1: String = stacks
+            .iter()
+            .map(|stack| *stack.last().unwrap() as char)
+            .collect();
+        part1.into()
     }
 
-    let part2: String = stacks
-        .iter()
-        .map(|stack| *stack.last().unwrap() as char)
-        .collect();
+    fn part2((stacks_input, moves): &Self::Parsed) -> Answer {
+        let mut stacks = stacks_input.clone();
+        for action in moves {
+            let idx = stacks[action.src as usize - 1].len() - action.num;
 
-    if print {
-        println!("Day {}.  Part 1: {}", DAY, part1);
-    }
-    assert_eq!(part1, if test_mode { "CMZ" } else { "GFTNRBZPF" });
+            // Unpleasant copy, since the borrow checker is challenging here.
+            let moving = stacks[action.src as usize - 1][idx..].to_owned();
+            stacks[action.dst as usize - 1].extend_from_slice(&moving);
+            stacks[action.src as usize - 1].truncate(idx);
+        }
 
-    if print {
-        println!("Day {}.  Part 2: {}", DAY, part2);
+        let part2: String = stacks
+            .iter()
+            .map(|stack| *stack.last().unwrap() as char)
+            .collect();
+        part2.into()
     }
-    assert_eq!(part2, if test_mode { "MCD" } else { "VRQWPDSGP" });
 }
 
-const TEST_EXAMPLE: &'static str = "    [D]    
+const TEST_EXAMPLE: &str = "    [D]    
 [N] [C]    
 [Z] [M] [P]
  1   2   3 
@@ -121,3 +214,38 @@ move 1 from 2 to 1
 move 3 from 1 to 3
 move 2 from 2 to 1
 move 1 from 1 to 2";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::testing::{part1, part2};
+
+    #[test]
+    fn example_part1() {
+        assert_eq!(part1::<Day05>(TEST_EXAMPLE), "CMZ".into());
+    }
+
+    #[test]
+    fn example_part2() {
+        assert_eq!(part2::<Day05>(TEST_EXAMPLE), "MCD".into());
+    }
+
+    #[test]
+    fn trimmed_drawing() {
+        let trimmed: Vec<&str> = TEST_EXAMPLE.lines().map(str::trim_end).collect();
+        assert_eq!(part1::<Day05>(&trimmed.join("\n")), "CMZ".into());
+    }
+
+    #[test]
+    fn impossible_moves() {
+        let negative = TEST_EXAMPLE.replace("move 1 from 2 to 1", "move -1 from 2 to 1");
+        let err = Day05::parse(&negative).unwrap_err();
+        assert_eq!((err.line, err.column), (6, 6));
+
+        // Stack 2 only has two crates left by the third move.
+        let too_many = TEST_EXAMPLE.replace("move 2 from 2 to 1", "move 3 from 2 to 1");
+        let err = Day05::parse(&too_many).unwrap_err();
+        assert_eq!((err.line, err.column), (8, 6));
+        assert_eq!(err.expected, "at most 2 crates, as stack 2 has");
+    }
+}
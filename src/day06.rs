@@ -1,7 +1,8 @@
-const DAY: i32 = 6;
+use crate::solution::{Answer, Solution};
+use crate::utils::{lines, ParseError};
 
 fn first_unique(input: &[u8], len: usize) -> i32 {
-    let mut active = vec![0u8; 26];
+    let mut active = [0u8; 26];
     let mut active_entries = 0;
     for i in 0..input.len() {
         // Tracks input[i]
@@ -28,28 +29,67 @@ fn first_unique(input: &[u8], len: usize) -> i32 {
     -1
 }
 
-pub fn day06(test_mode: bool, print: bool) {
-    let file_str = std::fs::read_to_string(format!("inputs/input{:02}.txt", DAY)).unwrap();
-    let input_str = if test_mode {
-        TEST_EXAMPLE
-    } else {
-        &file_str.trim_end()
-    };
+pub struct Day06;
 
-    let input = input_str.as_bytes();
+impl Solution for Day06 {
+    const DAY: u32 = 6;
+    const EXAMPLE: &'static str = TEST_EXAMPLE;
 
-    let part1 = first_unique(input, 4);
-    let part2 = first_unique(input, 14);
+    type Parsed = Vec<u8>;
 
-    if print {
-        println!("Day {}.  Part 1: {}", DAY, part1);
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        let mut lines = lines(input);
+        let mut line = lines.next_line("the datastream")?;
+        let mut buffer = Vec::with_capacity(line.rest().len());
+        while !line.at_end() {
+            buffer.push(line.char_where("a lowercase letter", |ch| ch.is_ascii_lowercase())? as u8);
+        }
+        match lines.next() {
+            Some(extra) => Err(extra.error("just the one line")),
+            None => Ok(buffer),
+        }
+    }
+
+    fn part1(input: &Self::Parsed) -> Answer {
+        first_unique(input, 4).into()
     }
-    assert_eq!(part1, if test_mode { 7 } else { 1275 });
 
-    if print {
-        println!("Day {}.  Part 2: {}", DAY, part2);
+    fn part2(input: &Self::Parsed) -> Answer {
+        first_unique(input, 14).into()
     }
-    assert_eq!(part2, if test_mode { 19 } else { 3605 });
 }
 
-const TEST_EXAMPLE: &'static str = "mjqjpqmgbljsphdztnvjfqwrcgsmlb";
\ No newline at end of file
+const TEST_EXAMPLE: &str = "mjqjpqmgbljsphdztnvjfqwrcgsmlb";
+
+// The other start-of-packet examples: (buffer, part 1, part 2).
+#[cfg(test)]
+const MORE_EXAMPLES: [(&str, i32, i32); 4] = [
+    ("bvwbjplbgvbhsrlpgdmjqwftvncz", 5, 23),
+    ("nppdvjthqldpwncqszvftbrmjlhg", 6, 23),
+    ("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", 10, 29),
+    ("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", 11, 26),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::testing::{part1, part2};
+
+    #[test]
+    fn example_part1() {
+        assert_eq!(part1::<Day06>(TEST_EXAMPLE), 7.into());
+    }
+
+    #[test]
+    fn example_part2() {
+        assert_eq!(part2::<Day06>(TEST_EXAMPLE), 19.into());
+    }
+
+    #[test]
+    fn more_examples() {
+        for (example, expected1, expected2) in MORE_EXAMPLES {
+            assert_eq!(part1::<Day06>(example), expected1.into(), "{}", example);
+            assert_eq!(part2::<Day06>(example), expected2.into(), "{}", example);
+        }
+    }
+}
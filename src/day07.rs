@@ -2,10 +2,11 @@ use std::cmp::min;
 
 use rustc_hash::FxHashMap;
 
-const DAY: i32 = 7;
+use crate::solution::{Answer, Solution};
+use crate::utils::{lines, lint_lines, Line, ParseError};
 
 #[derive(Debug)]
-struct Dir {
+pub struct Dir {
     subdirs: Vec<String>,
     files: Vec<(String, usize)>,
 }
@@ -19,7 +20,7 @@ impl Dir {
     }
 }
 
-type DirTree = FxHashMap<String, Dir>;
+pub type DirTree = FxHashMap<String, Dir>;
 
 fn walk_for_sizes(tree: &DirTree, path: &str, sum_small: &mut usize) -> usize {
     let dir = tree.get(path).unwrap();
@@ -69,88 +70,130 @@ fn find_smallest_above(tree: &DirTree, threshold: usize, path: &str) -> (usize,
     (size, smallest_above)
 }
 
-pub fn day07(test_mode: bool, print: bool) {
-    let file_str = std::fs::read_to_string(format!("inputs/input{:02}.txt", DAY)).unwrap();
-    let input_str = if test_mode {
-        TEST_EXAMPLE
+enum Cd<'a> {
+    Root,
+    Up,
+    Into(&'a str),
+}
+
+// "$ ls", or "$ cd" somewhere (None for ls).
+fn parse_command<'a>(mut cmd: Line<'a>) -> Result<Option<Cd<'a>>, ParseError> {
+    cmd.literal("$ ")?;
+    let cd = if cmd.try_literal("ls") {
+        None
+    } else if cmd.try_literal("cd ") {
+        if cmd.try_literal("/") {
+            Some(Cd::Root)
+        } else if cmd.try_literal("..") {
+            Some(Cd::Up)
+        } else {
+            Some(Cd::Into(cmd.word("a directory name")?))
+        }
+    } else {
+        return Err(cmd.error("\"cd\" or \"ls\""));
+    };
+    cmd.end()?;
+    Ok(cd)
+}
+
+enum Entry<'a> {
+    Dir(&'a str),
+    File(&'a str, usize),
+}
+
+// "dir a" or "14848514 b.txt", from ls.
+fn parse_entry<'a>(mut line: Line<'a>) -> Result<Entry<'a>, ParseError> {
+    let entry = if line.try_literal("dir ") {
+        Entry::Dir(line.word("a directory name")?)
     } else {
-        &file_str.trim_end()
+        let size = line.integer()?;
+        line.literal(" ")?;
+        Entry::File(line.word("a file name")?, size)
     };
+    line.end()?;
+    Ok(entry)
+}
+
+pub struct Day07;
 
-    // State
-    let mut cwd = vec!["<unknown>"];
-    let mut tree = FxHashMap::<String, Dir>::with_capacity_and_hasher(256, Default::default());
+impl Solution for Day07 {
+    const DAY: u32 = 7;
+    const EXAMPLE: &'static str = TEST_EXAMPLE;
 
-    let mut line_iter = input_str.lines().peekable();
+    type Parsed = DirTree;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        // State
+        let mut cwd = vec!["<unknown>"];
+        let mut tree = FxHashMap::<String, Dir>::with_capacity_and_hasher(256, Default::default());
+
+        let mut lines = lines(input);
 
-    loop {
         // Command parsing
-        let cmd_opt = line_iter.next();
-        if let Some(cmd) = cmd_opt {
-            let pieces: Vec<&str> = cmd.split(" ").collect();
-            if pieces[1] == "ls" {
-                // Fallthrough
-            } else {
-                // cd
-                if pieces[2] == "/" {
+        while let Some(cmd) = lines.next() {
+            match parse_command(cmd)? {
+                None => (),
+                Some(Cd::Root) => {
                     cwd.truncate(0);
                     cwd.push("");
-                } else if pieces[2] == ".." {
-                    cwd.truncate(cwd.len() - 1);
-                } else {
-                    cwd.push(pieces[2]);
                 }
+                Some(Cd::Up) => {
+                    cwd.pop();
+                }
+                Some(Cd::Into(dir)) => cwd.push(dir),
             }
-        } else {
-            break;
-        }
 
-        let path = cwd.join("/");
-        let dir = tree.entry(path).or_insert_with(Dir::new);
+            let path = cwd.join("/");
+            let dir = tree.entry(path).or_insert_with(Dir::new);
 
-        // Output parsing
-        loop {
-            if let Some(line) = line_iter.peek() {
-                if line.chars().next().unwrap() == '$' {
+            // Output parsing
+            while let Some(line) = lines.peek() {
+                if line.rest().starts_with('$') {
                     break;
                 }
 
-                let mut pieces = line.split(" ");
-                let p1 = pieces.next().unwrap();
-                let p2 = pieces.next().unwrap();
-                if p1 == "dir" {
-                    dir.subdirs.push(p2.to_string());
-                } else {
-                    dir.files.push((p2.to_string(), p1.parse().unwrap()));
+                match parse_entry(line)? {
+                    Entry::Dir(name) => dir.subdirs.push(name.to_string()),
+                    Entry::File(name, size) => dir.files.push((name.to_string(), size)),
                 }
-            } else {
-                break;
+                lines.next();
             }
-            line_iter.next();
         }
+        Ok(tree)
     }
 
-    let mut part1 = 0;
-    let used_size = walk_for_sizes(&tree, "", &mut part1);
-
-    const DISK_SIZE: usize = 70000000;
-    const DISK_NEED: usize = 30000000;
-
-    let need_to_delete = DISK_NEED - (DISK_SIZE - used_size);
-    let (_, part2) = find_smallest_above(&tree, need_to_delete, "");
+    fn lint(input: &str) -> Vec<ParseError> {
+        // The transcript has to start with a command.
+        let mut first = true;
+        lint_lines(input, |line| {
+            let is_command = first || line.rest().starts_with('$');
+            first = false;
+            if is_command {
+                parse_command(line).map(drop)
+            } else {
+                parse_entry(line).map(drop)
+            }
+        })
+    }
 
-    if print {
-        println!("Day {}.  Part 1: {}", DAY, part1);
+    fn part1(tree: &Self::Parsed) -> Answer {
+        let mut part1 = 0;
+        walk_for_sizes(tree, "", &mut part1);
+        part1.into()
     }
-    assert_eq!(part1, if test_mode { 95437 } else { 1428881 });
 
-    if print {
-        println!("Day {}.  Part 2: {}", DAY, part2);
+    fn part2(tree: &Self::Parsed) -> Answer {
+        const DISK_SIZE: usize = 70000000;
+        const DISK_NEED: usize = 30000000;
+
+        let used_size = walk_for_sizes(tree, "", &mut 0);
+        let need_to_delete = DISK_NEED - (DISK_SIZE - used_size);
+        let (_, part2) = find_smallest_above(tree, need_to_delete, "");
+        part2.into()
     }
-    assert_eq!(part2, if test_mode { 24933642 } else { 10475598 });
 }
 
-const TEST_EXAMPLE: &'static str = "$ cd /
+const TEST_EXAMPLE: &str = "$ cd /
 $ ls
 dir a
 14848514 b.txt
@@ -173,3 +216,19 @@ $ ls
 8033020 d.log
 5626152 d.ext
 7214296 k";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::testing::{part1, part2};
+
+    #[test]
+    fn example_part1() {
+        assert_eq!(part1::<Day07>(TEST_EXAMPLE), 95437.into());
+    }
+
+    #[test]
+    fn example_part2() {
+        assert_eq!(part2::<Day07>(TEST_EXAMPLE), 24933642.into());
+    }
+}
@@ -1,154 +1,216 @@
 use rustc_hash::FxHashSet;
 
-use crate::utils::Grid;
+use crate::solution::{Answer, Solution};
+use crate::utils::{lines, Grid, ParseError};
+
+// The trees seen looking out from (row, col) each way, nearest first. For the
+// reference versions of the parts.
+fn lines_of_sight(grid: &Grid<u8>, row: usize, col: usize) -> [Vec<u8>; 4] {
+    [
+        (0..row).rev().map(|r| grid[(r, col)]).collect(),
+        (row + 1..grid.rows).map(|r| grid[(r, col)]).collect(),
+        (0..col).rev().map(|c| grid[(row, c)]).collect(),
+        (col + 1..grid.cols).map(|c| grid[(row, c)]).collect(),
+    ]
+}
+
+pub struct Day08;
 
-const DAY: i32 = 8;
+impl Solution for Day08 {
+    const DAY: u32 = 8;
+    const EXAMPLE: &'static str = TEST_EXAMPLE;
 
-pub fn day08(test_mode: bool, print: bool) {
-    let file_str = std::fs::read_to_string(format!("inputs/input{:02}.txt", DAY)).unwrap();
-    let input_str = if test_mode {
-        TEST_EXAMPLE
-    } else {
-        &file_str.trim_end()
-    };
+    type Parsed = Grid<u8>;
 
-    let grid = {
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
         let mut grid_data = Vec::with_capacity(100 * 100);
-        let mut cols = 0;
+        let mut lines = lines(input);
+
+        // The first row sets the width.
+        let mut line = lines.next_line("a row of trees")?;
+        let cols = line.rest().len();
+        if cols == 0 {
+            return Err(line.error("a tree height"));
+        }
+        loop {
+            for _ in 0..cols {
+                grid_data.push(line.char_where("a tree height", |ch| ch.is_ascii_digit())? as u8 - b'0');
+            }
+            line.end()?;
 
-        for line in input_str.lines() {
-            let line_bytes = line.as_bytes();
-            cols = line_bytes.len();
-            for ch in line_bytes {
-                grid_data.push(ch - b'0');
+            match lines.next() {
+                Some(next) => line = next,
+                None => break,
             }
         }
 
-        Grid {
-            cols: cols,
+        Ok(Grid {
+            cols,
             rows: grid_data.len() / cols,
             data: grid_data,
-        }
-    };
-    // println!("{}", grid.fmt_compact());
+        })
+    }
 
-    let mut visible = FxHashSet::<(usize, usize)>::with_capacity_and_hasher(256, Default::default());
+    fn part1(grid: &Self::Parsed) -> Answer {
+        let mut visible = FxHashSet::<(usize, usize)>::with_capacity_and_hasher(256, Default::default());
 
-    // Horizontal
-    for i in 1..(grid.rows - 1) {
-        // Left -> Right
-        let mut tallest = grid[(i, 0)];
-        for j in 1..(grid.cols - 1) {
-            let tree = grid[(i, j)];
-            if tree > tallest {
-                visible.insert((i, j));
-                tallest = tree;
+        // Horizontal
+        for i in 1..(grid.rows - 1) {
+            // Left -> Right
+            let mut tallest = grid[(i, 0)];
+            for j in 1..(grid.cols - 1) {
+                let tree = grid[(i, j)];
+                if tree > tallest {
+                    visible.insert((i, j));
+                    tallest = tree;
+                }
             }
-        }
 
-        // Right -> Left
-        let mut tallest = grid[(i, grid.cols - 1)];
-        for j in (1..(grid.cols - 1)).rev() {
-            let tree = grid[(i, j)];
-            if tree > tallest {
-                visible.insert((i, j));
-                tallest = tree;
+            // Right -> Left
+            let mut tallest = grid[(i, grid.cols - 1)];
+            for j in (1..(grid.cols - 1)).rev() {
+                let tree = grid[(i, j)];
+                if tree > tallest {
+                    visible.insert((i, j));
+                    tallest = tree;
+                }
             }
         }
-    }
 
-    // Vertical
-    for j in 1..(grid.cols - 1) {
-        // Top -> bottom
-        let mut tallest = grid[(0, j)];
-        for i in 1..(grid.rows - 1) {
-            let tree = grid[(i, j)];
-            if tree > tallest { 
-                visible.insert((i, j));
-                tallest = tree;
+        // Vertical
+        for j in 1..(grid.cols - 1) {
+            // Top -> bottom
+            let mut tallest = grid[(0, j)];
+            for i in 1..(grid.rows - 1) {
+                let tree = grid[(i, j)];
+                if tree > tallest { 
+                    visible.insert((i, j));
+                    tallest = tree;
+                }
             }
-        }
         
-        // Bottom -> Top
-        let mut tallest = grid[(grid.rows - 1, j)];
-        for i in (1..(grid.rows - 1)).rev() {
-            let tree = grid[(i, j)];
-            if tree > tallest {
-                visible.insert((i, j));
-                tallest = tree;
+            // Bottom -> Top
+            let mut tallest = grid[(grid.rows - 1, j)];
+            for i in (1..(grid.rows - 1)).rev() {
+                let tree = grid[(i, j)];
+                if tree > tallest {
+                    visible.insert((i, j));
+                    tallest = tree;
+                }
             }
         }
-    }
 
-    let part1 = visible.len() + 2 * grid.rows + 2 * grid.cols - 4;
-
-    let mut scores = Grid::filled(grid.rows, grid.cols, 1);
+        let part1 = visible.len() + 2 * grid.rows + 2 * grid.cols - 4;
+        part1.into()
+    }
 
-    // Horizontal
-    for i in 0..grid.rows {
-        // Left -> Right
-        let mut blockers = [0; 10];
-        for j in 0..grid.cols {
-            let tree = grid[(i, j)];
-            scores[(i, j)] *= j - blockers[tree as usize];
+    fn part2(grid: &Self::Parsed) -> Answer {
+        let mut scores = Grid::filled(grid.rows, grid.cols, 1);
 
-            for k in 0..=tree {
-                blockers[k as usize] = j;
+        // Horizontal
+        for i in 0..grid.rows {
+            // Left -> Right
+            let mut blockers = [0; 10];
+            for j in 0..grid.cols {
+                let tree = grid[(i, j)];
+                scores[(i, j)] *= j - blockers[tree as usize];
+
+                for k in 0..=tree {
+                    blockers[k as usize] = j;
+                }
             }
-        }
 
-        // Right -> Left
-        let mut blockers = [grid.cols - 1; 10];
-        for j in (0..grid.cols).rev() {
-            let tree = grid[(i, j)];
-            scores[(i, j)] *= blockers[tree as usize] - j;
+            // Right -> Left
+            let mut blockers = [grid.cols - 1; 10];
+            for j in (0..grid.cols).rev() {
+                let tree = grid[(i, j)];
+                scores[(i, j)] *= blockers[tree as usize] - j;
 
-            for k in 0..=tree {
-                blockers[k as usize] = j;
+                for k in 0..=tree {
+                    blockers[k as usize] = j;
+                }
             }
         }
-    }
 
-    // Vertical
-    for j in 0..grid.cols {
-        // Top -> bottom
-        let mut blockers = [0; 10];
-        for i in 0..grid.rows {
-            let tree = grid[(i, j)];
-            scores[(i, j)] *= i - blockers[tree as usize];
-
-            for k in 0..=tree {
-                blockers[k as usize] = i;
+        // Vertical
+        for j in 0..grid.cols {
+            // Top -> bottom
+            let mut blockers = [0; 10];
+            for i in 0..grid.rows {
+                let tree = grid[(i, j)];
+                scores[(i, j)] *= i - blockers[tree as usize];
+
+                for k in 0..=tree {
+                    blockers[k as usize] = i;
+                }
             }
-        }
         
-        // Bottom -> Top
-        let mut blockers = [grid.rows - 1; 10];
-        for i in (0..grid.rows).rev() {
-            let tree = grid[(i, j)];
-            scores[(i, j)] *= blockers[tree as usize] - i;
-
-            for k in 0..=tree {
-                blockers[k as usize] = i;
+            // Bottom -> Top
+            let mut blockers = [grid.rows - 1; 10];
+            for i in (0..grid.rows).rev() {
+                let tree = grid[(i, j)];
+                scores[(i, j)] *= blockers[tree as usize] - i;
+
+                for k in 0..=tree {
+                    blockers[k as usize] = i;
+                }
             }
         }
-    }
 
-    let part2 = *scores.data.iter().max().unwrap() as i32;
+        let part2 = *scores.data.iter().max().unwrap() as i32;
+        part2.into()
+    }
 
-    if print {
-        println!("Day {}.  Part 1: {}", DAY, part1);
+    fn reference1(grid: &Self::Parsed) -> Option<Answer> {
+        let mut visible: usize = 0;
+        for row in 0..grid.rows {
+            for col in 0..grid.cols {
+                let tree = grid[(row, col)];
+                if lines_of_sight(grid, row, col).iter().any(|line| line.iter().all(|&other| other < tree)) {
+                    visible += 1;
+                }
+            }
+        }
+        Some(visible.into())
     }
-    assert_eq!(part1, if test_mode { 21 } else { 1854 });
 
-    if print {
-        println!("Day {}.  Part 2: {}", DAY, part2);
+    fn reference2(grid: &Self::Parsed) -> Option<Answer> {
+        let mut best = 0;
+        for row in 0..grid.rows {
+            for col in 0..grid.cols {
+                let tree = grid[(row, col)];
+                let score: usize = lines_of_sight(grid, row, col)
+                    .iter()
+                    .map(|line| match line.iter().position(|&other| other >= tree) {
+                        Some(blocked) => blocked + 1,
+                        None => line.len(),
+                    })
+                    .product();
+                best = best.max(score);
+            }
+        }
+        Some(best.into())
     }
-    assert_eq!(part2, if test_mode { 8 } else { 527340 });
 }
 
-const TEST_EXAMPLE: &'static str = "30373
+const TEST_EXAMPLE: &str = "30373
 25512
 65332
 33549
 35390";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::testing::{part1, part2};
+
+    #[test]
+    fn example_part1() {
+        assert_eq!(part1::<Day08>(TEST_EXAMPLE), 21.into());
+    }
+
+    #[test]
+    fn example_part2() {
+        assert_eq!(part2::<Day08>(TEST_EXAMPLE), 8.into());
+    }
+}
@@ -1,10 +1,10 @@
 use rustc_hash::FxHashSet;
 
-use crate::utils::{Pt, BorrowPairMut};
+use crate::solution::{Answer, Solution};
+use crate::utils::{lines, lint_lines, BorrowPairMut, Line, ParseError, Pt};
 
-const DAY: i32 = 9;
-
-enum Dir {
+#[derive(Clone, Copy)]
+pub enum Dir {
     R,
     U,
     L,
@@ -19,84 +19,81 @@ fn snap(lead: &Pt, follow: &mut Pt) {
     }
 }
 
-pub fn day09(test_mode: bool, print: bool) {
-    let file_str = std::fs::read_to_string(format!("inputs/input{:02}.txt", DAY)).unwrap();
-    let input_str = if test_mode {
-        TEST_EXAMPLE
-    } else {
-        &file_str.trim_end()
-    };
-
-    let moves = {
-        let mut moves = Vec::<(Dir, i32)>::with_capacity(256);
-        for line in input_str.lines() {
-            let mut pieces = line.split(" ");
-            let dir = match pieces.next().unwrap() {
-                "R" => Dir::R,
-                "L" => Dir::L,
-                "U" => Dir::U,
-                "D" => Dir::D,
-                _ => unreachable!(),
-            };
-            moves.push((dir, pieces.next().unwrap().parse().unwrap()));
-        }
-        moves
-    };
-
-    let mut head = Pt::at(0, 0);
-    let mut tail = Pt::at(0, 0);
-    let mut tail_been = FxHashSet::<Pt>::with_capacity_and_hasher(2048, Default::default());
-
-    for (dir, distance) in &moves {
-        for _ in 0..*distance {
-            match dir {
-                Dir::R => head.x += 1,
-                Dir::U => head.y += 1,
-                Dir::L => head.x -= 1,
-                Dir::D => head.y -= 1,
-            }
+// "R 4"
+fn parse_move(mut line: Line) -> Result<(Dir, i32), ParseError> {
+    let dir = line.one_of(&[("R", Dir::R), ("L", Dir::L), ("U", Dir::U), ("D", Dir::D)])?;
+    line.literal(" ")?;
+    let steps = line.integer()?;
+    line.end()?;
+    Ok((dir, steps))
+}
 
-            snap(&head, &mut tail);
+pub struct Day09;
 
-            tail_been.insert(tail.clone());
-        }
+impl Solution for Day09 {
+    const DAY: u32 = 9;
+    const EXAMPLE: &'static str = TEST_EXAMPLE;
+
+    type Parsed = Vec<(Dir, i32)>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        lines(input).map(parse_move).collect()
     }
 
-    let part1 = tail_been.len();
-    if print {
-        println!("Day {}.  Part 1: {}", DAY, part1);
+    fn lint(input: &str) -> Vec<ParseError> {
+        lint_lines(input, parse_move)
     }
-    assert_eq!(part1, if test_mode { 13 } else { 6236 });
-
-    let mut rope = vec![Pt::at(0, 0); 10];
-    let mut tail_been = FxHashSet::<Pt>::with_capacity_and_hasher(2048, Default::default());
-
-    for (dir, distance) in &moves {
-        for _ in 0..*distance {
-            match dir {
-                Dir::R => rope[0].x += 1,
-                Dir::U => rope[0].y += 1,
-                Dir::L => rope[0].x -= 1,
-                Dir::D => rope[0].y -= 1,
-            }
 
-            for i in 0..(rope.len() - 1) {
-                let (lead, follow) = rope.borrow_pair_mut(i, i + 1);
-                snap(lead, follow);
-            }
+    fn part1(moves: &Self::Parsed) -> Answer {
+        let mut head = Pt::at(0, 0);
+        let mut tail = Pt::at(0, 0);
+        let mut tail_been = FxHashSet::<Pt>::with_capacity_and_hasher(2048, Default::default());
 
-            tail_been.insert(rope.last().unwrap().clone());
+        for (dir, distance) in moves {
+            for _ in 0..*distance {
+                match dir {
+                    Dir::R => head.x += 1,
+                    Dir::U => head.y += 1,
+                    Dir::L => head.x -= 1,
+                    Dir::D => head.y -= 1,
+                }
+
+                snap(&head, &mut tail);
+
+                tail_been.insert(tail.clone());
+            }
         }
+
+        tail_been.len().into()
     }
 
-    let part2 = tail_been.len();
-    if print {
-        println!("Day {}.  Part 2: {}", DAY, part2);
+    fn part2(moves: &Self::Parsed) -> Answer {
+        let mut rope = vec![Pt::at(0, 0); 10];
+        let mut tail_been = FxHashSet::<Pt>::with_capacity_and_hasher(2048, Default::default());
+
+        for (dir, distance) in moves {
+            for _ in 0..*distance {
+                match dir {
+                    Dir::R => rope[0].x += 1,
+                    Dir::U => rope[0].y += 1,
+                    Dir::L => rope[0].x -= 1,
+                    Dir::D => rope[0].y -= 1,
+                }
+
+                for i in 0..(rope.len() - 1) {
+                    let (lead, follow) = rope.borrow_pair_mut(i, i + 1);
+                    snap(lead, follow);
+                }
+
+                tail_been.insert(rope.last().unwrap().clone());
+            }
+        }
+
+        tail_been.len().into()
     }
-    assert_eq!(part2, if test_mode { 1 } else { 2449 });
 }
 
-const TEST_EXAMPLE: &'static str = "R 4
+const TEST_EXAMPLE: &str = "R 4
 U 4
 L 3
 D 1
@@ -104,3 +101,35 @@ R 4
 D 1
 L 5
 R 2";
+
+// Part 2's larger example, where the tail actually moves.
+#[cfg(test)]
+const LARGER_EXAMPLE: &str = "R 5
+U 8
+L 8
+D 3
+R 17
+D 10
+L 25
+U 20";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::testing::{part1, part2};
+
+    #[test]
+    fn example_part1() {
+        assert_eq!(part1::<Day09>(TEST_EXAMPLE), 13.into());
+    }
+
+    #[test]
+    fn example_part2() {
+        assert_eq!(part2::<Day09>(TEST_EXAMPLE), 1.into());
+    }
+
+    #[test]
+    fn larger_example_part2() {
+        assert_eq!(part2::<Day09>(LARGER_EXAMPLE), 36.into());
+    }
+}
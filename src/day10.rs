@@ -1,35 +1,29 @@
-const DAY: i32 = 10;
+use crate::solution::{Answer, Solution};
+use crate::utils::{lines, lint_lines, Line, ParseError};
 
-enum Op {
+pub enum Op {
     AddX(i32),
     Noop,
 }
 
-fn parse_instruction(line: &str) -> Op {
-    let mut pieces = line.split(" ");
-    match pieces.next().unwrap() {
-        "noop" => Op::Noop,
-        "addx" => Op::AddX(pieces.next().unwrap().parse().unwrap()),
-        _ => unreachable!(),
-    }
-}
-
-pub fn day10(test_mode: bool, print: bool) {
-    let file_str = std::fs::read_to_string(format!("inputs/input{:02}.txt", DAY)).unwrap();
-    let input_str = if test_mode {
-        TEST_EXAMPLE
+fn parse_instruction(mut line: Line) -> Result<Op, ParseError> {
+    let op = if line.try_literal("noop") {
+        Op::Noop
+    } else if line.try_literal("addx ") {
+        Op::AddX(line.integer()?)
     } else {
-        &file_str.trim_end()
+        return Err(line.error("\"noop\" or \"addx\""));
     };
+    line.end()?;
+    Ok(op)
+}
 
-    let program: Vec<Op> = input_str.lines().map(parse_instruction).collect();
-
+// Calls `on_cycle(cycle, x)` for each cycle of the program.
+fn for_each_cycle<F: FnMut(usize, i32)>(program: &[Op], mut on_cycle: F) {
     let mut cycle: usize = 1;
     let mut x: i32 = 1;
 
-    let mut part1 = 0;
-    let mut screen = [0u8; 6 * 40];
-    for instr in &program {
+    for instr in program {
         // How long to wait.
         let cycles_to_wait = match instr {
             Op::AddX(_) => 2,
@@ -38,15 +32,7 @@ pub fn day10(test_mode: bool, print: bool) {
 
         // Wait
         for _ in 0..cycles_to_wait {
-            if (cycle + 20) % 40 == 0 {
-                part1 += cycle as i32 * x;
-            }
-
-            let screen_position: usize = cycle - 1;
-            if (x - (screen_position as i32) % 40).abs() <= 1 {
-                screen[screen_position] = 1;
-            }
-
+            on_cycle(cycle, x);
             cycle += 1;
         }
 
@@ -56,53 +42,53 @@ pub fn day10(test_mode: bool, print: bool) {
             Op::Noop => (),
         }
     }
+}
 
-    if print {
-        println!("Day {}.  Part 1: {}", DAY, part1);
+pub struct Day10;
+
+impl Solution for Day10 {
+    const DAY: u32 = 10;
+    const EXAMPLE: &'static str = TEST_EXAMPLE;
+
+    type Parsed = Vec<Op>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        lines(input).map(parse_instruction).collect()
     }
-    assert_eq!(part1, if test_mode { 13140 } else { 14720 });
-
-    if print {
-        println!("Day {}.  Part 2:", DAY);
-        for i in 0..6 {
-            println!(
-                "{}",
-                &screen[(i * 40)..((i + 1) * 40)]
-                    .iter()
-                    .map(|k| if *k > 0 { '#' } else { '.' })
-                    .collect::<String>()
-            );
-        }
+
+    fn lint(input: &str) -> Vec<ParseError> {
+        lint_lines(input, parse_instruction)
     }
 
-    #[rustfmt::skip]
-    const PART2_TEST_ANSWER: [u8; 6*40] = [
-        1, 1, 0, 0, 1, 1, 0, 0, 1, 1, 0, 0, 1, 1, 0, 0, 1, 1, 0, 0, 1, 1, 0, 0, 1, 1, 0, 0, 1, 1, 0, 0, 1, 1, 0, 0, 1, 1, 0, 0, 
-        1, 1, 1, 0, 0, 0, 1, 1, 1, 0, 0, 0, 1, 1, 1, 0, 0, 0, 1, 1, 1, 0, 0, 0, 1, 1, 1, 0, 0, 0, 1, 1, 1, 0, 0, 0, 1, 1, 1, 0, 
-        1, 1, 1, 1, 0, 0, 0, 0, 1, 1, 1, 1, 0, 0, 0, 0, 1, 1, 1, 1, 0, 0, 0, 0, 1, 1, 1, 1, 0, 0, 0, 0, 1, 1, 1, 1, 0, 0, 0, 0, 
-        1, 1, 1, 1, 1, 0, 0, 0, 0, 0, 1, 1, 1, 1, 1, 0, 0, 0, 0, 0, 1, 1, 1, 1, 1, 0, 0, 0, 0, 0, 1, 1, 1, 1, 1, 0, 0, 0, 0, 0, 
-        1, 1, 1, 1, 1, 1, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 1, 1, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 1, 1, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 
-        1, 1, 1, 1, 1, 1, 1, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 1, 1, 1, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 1, 1, 1, 0, 0, 0, 0, 0];
-    #[rustfmt::skip]
-    const PART2_ANSWER: [u8; 6*40] = [
-        1, 1, 1, 1, 0, 1, 1, 1, 1, 0, 1, 1, 1, 0, 0, 1, 1, 1, 0, 0, 1, 1, 1, 0, 0, 1, 1, 1, 1, 0, 1, 1, 1, 1, 0, 1, 1, 1, 1, 0, 
-        1, 0, 0, 0, 0, 0, 0, 0, 1, 0, 1, 0, 0, 1, 0, 1, 0, 0, 1, 0, 1, 0, 0, 1, 0, 1, 0, 0, 0, 0, 0, 0, 0, 1, 0, 1, 0, 0, 0, 0, 
-        1, 1, 1, 0, 0, 0, 0, 1, 0, 0, 1, 1, 1, 0, 0, 1, 0, 0, 1, 0, 1, 1, 1, 0, 0, 1, 1, 1, 0, 0, 0, 0, 1, 0, 0, 1, 1, 1, 0, 0, 
-        1, 0, 0, 0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 1, 0, 1, 1, 1, 0, 0, 1, 0, 0, 1, 0, 1, 0, 0, 0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 0, 0, 
-        1, 0, 0, 0, 0, 1, 0, 0, 0, 0, 1, 0, 0, 1, 0, 1, 0, 0, 0, 0, 1, 0, 0, 1, 0, 1, 0, 0, 0, 0, 1, 0, 0, 0, 0, 1, 0, 0, 0, 0, 
-        1, 0, 0, 0, 0, 1, 1, 1, 1, 0, 1, 1, 1, 0, 0, 1, 0, 0, 0, 0, 1, 1, 1, 0, 0, 1, 0, 0, 0, 0, 1, 1, 1, 1, 0, 1, 0, 0, 0, 0];
-    // FZBPBFZF
-    assert_eq!(
-        screen,
-        if test_mode {
-            PART2_TEST_ANSWER
-        } else {
-            PART2_ANSWER
-        }
-    );
+    fn part1(program: &Self::Parsed) -> Answer {
+        let mut part1 = 0;
+        for_each_cycle(program, |cycle, x| {
+            if (cycle + 20).is_multiple_of(40) {
+                part1 += cycle as i32 * x;
+            }
+        });
+        part1.into()
+    }
+
+    fn part2(program: &Self::Parsed) -> Answer {
+        let mut screen = [0u8; 6 * 40];
+        for_each_cycle(program, |cycle, x| {
+            let screen_position: usize = cycle - 1;
+            if (x - (screen_position as i32) % 40).abs() <= 1 {
+                screen[screen_position] = 1;
+            }
+        });
+
+        Answer::Screen(
+            screen
+                .chunks(40)
+                .map(|row| row.iter().map(|k| if *k > 0 { '#' } else { '.' }).collect())
+                .collect(),
+        )
+    }
 }
 
-const TEST_EXAMPLE: &'static str = "addx 15
+const TEST_EXAMPLE: &str = "addx 15
 addx -11
 addx 6
 addx -3
@@ -247,4 +233,28 @@ addx -6
 addx -11
 noop
 noop
-noop";
\ No newline at end of file
+noop";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::testing::{part1, part2};
+
+    #[test]
+    fn example_part1() {
+        assert_eq!(part1::<Day10>(TEST_EXAMPLE), 13140.into());
+    }
+
+    #[test]
+    fn example_part2() {
+        let screen = vec![
+            "##..##..##..##..##..##..##..##..##..##..",
+            "###...###...###...###...###...###...###.",
+            "####....####....####....####....####....",
+            "#####.....#####.....#####.....#####.....",
+            "######......######......######......####",
+            "#######.......#######.......#######.....",
+        ];
+        assert_eq!(part2::<Day10>(TEST_EXAMPLE), Answer::Screen(screen.into_iter().map(String::from).collect()));
+    }
+}
@@ -1,198 +1,245 @@
-const DAY: i32 = 11;
+use crate::solution::{Answer, Solution};
+use crate::utils::{lines, Line, Lines, ParseError};
 
-#[derive(Debug)]
-enum Op {
+#[derive(Clone, Copy, Debug)]
+pub enum Op {
     Add,
     Mul,
 }
 
 #[derive(Debug)]
-struct Rule {
+pub struct Rule {
     op: Op,
     op_value: Option<i32>,
     test_divisible: i32,
-    true_monkey: i32,
-    false_monkey: i32,
+    true_monkey: usize,
+    false_monkey: usize,
+}
+
+fn monkey_business(inspections: Vec<usize>) -> usize {
+    let mut top = (0, 0);
+    for insp in inspections {
+        if insp > top.0 {
+            top.1 = top.0;
+            top.0 = insp;
+        } else if insp > top.1 {
+            top.1 = insp;
+        }
+    }
+    top.0 * top.1
 }
 
-pub fn day11(test_mode: bool, print: bool) {
-    let file_str = std::fs::read_to_string(format!("inputs/input{:02}.txt", DAY)).unwrap();
-    let input_str = if test_mode {
-        TEST_EXAMPLE
+// One monkey's six lines, for the monkey `number`. Where its throw targets
+// were goes in `targets`, to check them once all the monkeys are known. The
+// indentation varies, so it's ignored.
+fn parse_monkey<'a>(lines: &mut Lines<'a>, number: usize, targets: &mut Vec<Line<'a>>) -> Result<(Vec<i32>, Rule), ParseError> {
+    // "Monkey N:"
+    let mut line = lines.next_line("\"Monkey\"")?;
+    line.literal("Monkey ")?;
+    let at = line.clone();
+    if line.integer::<usize>()? != number {
+        return Err(at.error(format!("monkey {}", number)));
+    }
+    line.literal(":")?;
+    line.end()?;
+
+    // "Starting items: 79, 98"
+    let mut line = lines.next_line("\"Starting items\"")?;
+    line.skip_indent();
+    line.literal("Starting items: ")?;
+    let mut starting: Vec<i32> = vec![line.integer()?];
+    while line.try_literal(", ") {
+        starting.push(line.integer()?);
+    }
+    line.end()?;
+
+    // Operation: new = old * 19
+    let mut line = lines.next_line("\"Operation\"")?;
+    line.skip_indent();
+    line.literal("Operation: new = old ")?;
+    let op = line.one_of(&[("+", Op::Add), ("*", Op::Mul)])?;
+    line.literal(" ")?;
+    let op_value: Option<i32> = if line.try_literal("old") {
+        None
     } else {
-        &file_str.trim_end()
+        Some(line.integer()?)
     };
+    line.end()?;
+
+    // "Test: divisible by 23"
+    let mut line = lines.next_line("\"Test\"")?;
+    line.skip_indent();
+    line.literal("Test: divisible by ")?;
+    let at = line.clone();
+    let test_divisible: i32 = line.integer()?;
+    if test_divisible <= 0 {
+        return Err(at.error("a number above 0"));
+    }
+    line.end()?;
+
+    // "  If true: throw to monkey 2"
+    let mut line = lines.next_line("\"If true\"")?;
+    line.skip_indent();
+    line.literal("If true: throw to monkey ")?;
+    targets.push(line.clone());
+    let true_monkey: usize = line.integer()?;
+    line.end()?;
+
+    let mut line = lines.next_line("\"If false\"")?;
+    line.skip_indent();
+    line.literal("If false: throw to monkey ")?;
+    targets.push(line.clone());
+    let false_monkey: usize = line.integer()?;
+    line.end()?;
+
+    let rule = Rule {
+        op,
+        op_value,
+        test_divisible,
+        true_monkey,
+        false_monkey,
+    };
+    Ok((starting, rule))
+}
 
-    let mut line_iter = input_str.lines();
+// Throw targets have to be one of the `count` monkeys. Ones that aren't
+// numbers at all, negative ones included, were already reported.
+fn check_targets(targets: &[Line], count: usize) -> Vec<ParseError> {
+    targets
+        .iter()
+        .filter(|at| Line::clone(at).integer::<usize>().is_ok_and(|target| target >= count))
+        .map(|at| at.error(format!("a monkey from 0 to {}", count.saturating_sub(1))))
+        .collect()
+}
+
+pub struct Day11;
+
+impl Solution for Day11 {
+    const DAY: u32 = 11;
+    const EXAMPLE: &'static str = TEST_EXAMPLE;
+
+    type Parsed = (Vec<Vec<i32>>, Vec<Rule>);
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        let mut lines = lines(input);
 
-    let (starting, rules) = {
         let mut rules = Vec::with_capacity(32);
         let mut has: Vec<Vec<i32>> = Vec::with_capacity(32);
+        let mut targets = Vec::with_capacity(64);
 
         loop {
-            // "Monkey N:"
-            line_iter.next().unwrap();
-
-            // "Starting items: 79, 98"
-            let starting_str = line_iter.next().unwrap().split_once(": ").unwrap().1;
-            let starting: Vec<i32> = starting_str
-                .split(", ")
-                .map(|s| s.parse().unwrap())
-                .collect();
-
-            // Operation: new = old * 19
-            let op_str = line_iter.next().unwrap().split_once(" = ").unwrap().1;
-            let mut op_pieces = op_str.split(" ");
-            op_pieces.next().unwrap(); // "old"
-            let op = match op_pieces.next().unwrap() {
-                "+" => Op::Add,
-                "*" => Op::Mul,
-                _ => unreachable!(),
-            };
-            let op_value: Option<i32> = match op_pieces.next().unwrap() {
-                "old" => None,
-                value => Some(value.parse().unwrap()),
-            };
-
-            // "Test: divisible by 23"
-            let test_str = line_iter.next().unwrap().rsplit_once(" ").unwrap().1;
-            let test_divisible: i32 = test_str.parse().unwrap();
-
-            // "  If true: throw to monkey 2"
-            let true_monkey: i32 = line_iter
-                .next()
-                .unwrap()
-                .rsplit_once(" ")
-                .unwrap()
-                .1
-                .parse()
-                .unwrap();
-            let false_monkey: i32 = line_iter
-                .next()
-                .unwrap()
-                .rsplit_once(" ")
-                .unwrap()
-                .1
-                .parse()
-                .unwrap();
-
-            rules.push(Rule {
-                op,
-                op_value,
-                test_divisible,
-                true_monkey,
-                false_monkey,
-            });
+            let (starting, rule) = parse_monkey(&mut lines, rules.len(), &mut targets)?;
+            rules.push(rule);
             has.push(starting);
 
             // Eats the newline
-            if line_iter.next() == None {
-                break;
+            match lines.next() {
+                Some(line) => line.end()?,
+                None => break,
             }
         }
 
-        (has, rules)
-    };
+        if let Some(err) = check_targets(&targets, rules.len()).into_iter().next() {
+            return Err(err);
+        }
 
-    // Part 1
+        Ok((has, rules))
+    }
 
-    let mut has = starting.clone();
-    let mut inspections = vec![0; rules.len()];
+    fn lint(input: &str) -> Vec<ParseError> {
+        let mut errors = Vec::new();
+        let mut lines = lines(input);
+        let mut targets = Vec::new();
+        let mut count = 0;
+        loop {
+            if let Err(err) = parse_monkey(&mut lines, count, &mut targets) {
+                // Skips the rest of the monkey, unless the problem was that
+                // it ended early.
+                if !err.text.trim().is_empty() {
+                    lines.by_ref().find(|line| line.rest().trim().is_empty());
+                }
+                errors.push(err);
+            } else if let Some(line) = lines.next() {
+                errors.extend(line.end().err());
+            }
+            count += 1;
+            if lines.peek().is_none() {
+                break;
+            }
+        }
+        errors.extend(check_targets(&targets, count));
+        errors
+    }
 
-    for _ in 0..20 {
-        for i in 0..rules.len() {
-            let rule = &rules[i];
-            let items = has[i].clone();
-            inspections[i] += items.len();
+    fn part1((starting, rules): &Self::Parsed) -> Answer {
+        let mut has = starting.clone();
+        let mut inspections = vec![0; rules.len()];
+
+        for _ in 0..20 {
+            for (i, rule) in rules.iter().enumerate() {
+                let items = has[i].clone();
+                inspections[i] += items.len();
+
+                for mut item in items {
+                    let rhs = rule.op_value.unwrap_or(item);
+                    match rule.op {
+                        Op::Add => item += rhs,
+                        Op::Mul => item *= rhs,
+                    };
+
+                    item /= 3;
+                    let to_monkey = if (item % rule.test_divisible) == 0 {
+                        rule.true_monkey
+                    } else {
+                        rule.false_monkey
+                    };
+
+                    has[to_monkey].push(item);
+                }
+
+                has[i].truncate(0);
+            }
+        }
 
-            for mut item in items {
-                let rhs = rule.op_value.unwrap_or(item);
-                match rule.op {
-                    Op::Add => item += rhs,
-                    Op::Mul => item *= rhs,
-                };
+        monkey_business(inspections).into()
+    }
 
-                item /= 3;
-                let to_monkey = if (item % rule.test_divisible) == 0 {
-                    rule.true_monkey
-                } else {
-                    rule.false_monkey
-                };
+    fn part2((starting, rules): &Self::Parsed) -> Answer {
+        let mut has = starting.clone();
+        let mut inspections = vec![0; rules.len()];
 
+        let max_modulo: i64 = rules.iter().map(|rule| rule.test_divisible as i64).product();
 
-                has[to_monkey as usize].push(item);
-            }
+        for _ in 0..10_000 {
+            for (i, rule) in rules.iter().enumerate() {
+                let items = has[i].clone();
+                inspections[i] += items.len();
 
-            has[i].truncate(0);
-        }
-    }
+                for item in items {
+                    let rhs = rule.op_value.unwrap_or(item);
+                    let item = (match rule.op {
+                        Op::Add => (item as i64) + (rhs as i64),
+                        Op::Mul => (item as i64) * (rhs as i64),
+                    } % max_modulo) as i32;
 
-    let mut top = (0, 0);
-    for insp in inspections {
-        if insp > top.0 {
-            top.1 = top.0;
-            top.0 = insp;
-        } else if insp > top.1 {
-            top.1 = insp;
-        }
-    }
-    let part1 = top.0 * top.1;
+                    let to_monkey = if (item % rule.test_divisible) == 0 {
+                        rule.true_monkey
+                    } else {
+                        rule.false_monkey
+                    };
 
-    if print {
-        println!("Day {}.  Part 1: {}", DAY, part1);
-    }
-    assert_eq!(part1, if test_mode { 10605 } else { 58322 });
-
-    // Part 2
-    
-    let mut has = starting.clone();
-    let mut inspections = vec![0; rules.len()];
-
-    let max_modulo: i64 = rules.iter().map(|rule| rule.test_divisible as i64).product();
-
-    for _ in 0..10_000 {
-        for i in 0..rules.len() {
-            let rule = &rules[i];
-            let items = has[i].clone();
-            inspections[i] += items.len();
-
-            for item in items {
-                let rhs = rule.op_value.unwrap_or(item);
-                let item = (match rule.op {
-                    Op::Add => (item as i64) + (rhs as i64),
-                    Op::Mul => (item as i64) * (rhs as i64),
-                } % max_modulo) as i32;
-
-                let to_monkey = if (item % rule.test_divisible) == 0 {
-                    rule.true_monkey
-                } else {
-                    rule.false_monkey
-                };
-
-                has[to_monkey as usize].push(item);
-            }
+                    has[to_monkey].push(item);
+                }
 
-            has[i].truncate(0);
+                has[i].truncate(0);
+            }
         }
-    }
 
-    let mut top = (0, 0);
-    for insp in inspections {
-        if insp > top.0 {
-            top.1 = top.0;
-            top.0 = insp;
-        } else if insp > top.1 {
-            top.1 = insp;
-        }
+        monkey_business(inspections).into()
     }
-    let part2 = top.0 * top.1;
-    if print {
-        println!("Day {}.  Part 2: {}", DAY, part2);
-    }
-    assert_eq!(part2, if test_mode { 2713310158 } else { 13937702909 });
 }
 
-const TEST_EXAMPLE: &'static str = "Monkey 0:
+const TEST_EXAMPLE: &str = "Monkey 0:
 Starting items: 79, 98
 Operation: new = old * 19
 Test: divisible by 23
@@ -219,3 +266,30 @@ Operation: new = old + 3
 Test: divisible by 17
   If true: throw to monkey 0
   If false: throw to monkey 1";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::testing::{part1, part2};
+
+    #[test]
+    fn example_part1() {
+        assert_eq!(part1::<Day11>(TEST_EXAMPLE), 10605.into());
+    }
+
+    #[test]
+    fn example_part2() {
+        assert_eq!(part2::<Day11>(TEST_EXAMPLE), 2713310158i64.into());
+    }
+
+    #[test]
+    fn bad_divisor_and_target() {
+        let zero = TEST_EXAMPLE.replace("divisible by 23", "divisible by 0");
+        let err = Day11::parse(&zero).unwrap_err();
+        assert_eq!((err.line, err.column, err.expected.as_str()), (4, 20, "a number above 0"));
+
+        let negative = TEST_EXAMPLE.replace("true: throw to monkey 2", "true: throw to monkey -1");
+        let err = Day11::parse(&negative).unwrap_err();
+        assert_eq!((err.line, err.column), (5, 28));
+    }
+}
@@ -1,69 +1,21 @@
-use std::{cmp::min, collections::VecDeque};
+use std::collections::VecDeque;
 
-use crate::utils::{Grid, Pt};
-
-const DAY: i32 = 12;
-
-pub fn day12(test_mode: bool, print: bool) {
-    let file_str = std::fs::read_to_string(format!("inputs/input{:02}.txt", DAY)).unwrap();
-    let input_str = if test_mode {
-        TEST_EXAMPLE
-    } else {
-        &file_str.trim_end()
-    };
-
-    let (map, start_pos, end_pos) = {
-        let mut height = 0;
-        let mut start_pos = Pt::at(0, 0);
-        let mut end_pos = Pt::at(0, 0);
-        let mut data = Vec::with_capacity(2048);
-        for (i, line_str) in input_str.lines().enumerate() {
-            for (j, ch) in line_str.as_bytes().iter().enumerate() {
-                data.push(match ch {
-                    b'S' => {
-                        start_pos.set(j as i32, i as i32);
-                        b'a' - b'a'
-                    }
-                    b'E' => {
-                        end_pos.set(j as i32, i as i32);
-                        b'z' - b'a'
-                    }
-                    value @ b'a'..=b'z' => value - b'a',
-                    _ => unreachable!(),
-                });
-            }
-            height = i + 1;
-        }
-        (
-            Grid {
-                rows: height,
-                cols: data.len() / height,
-                data,
-            },
-            start_pos,
-            end_pos,
-        )
-    };
+use crate::solution::{Answer, Solution};
+use crate::utils::{lines, Grid, ParseError, Pt};
 
+// Walks downhill from the end, returning the steps to the first point that is `found`.
+fn steps_from_end<F: Fn(&Pt, u8) -> bool>(map: &Grid<u8>, end_pos: &Pt, found: F) -> i32 {
     let mut queue = VecDeque::with_capacity(map.rows * map.cols);
     queue.push_back((0, end_pos.clone()));
     let mut seen = Grid::filled(map.rows, map.cols, false);
-    let part1;
-    let mut part2 = i32::MAX;
     loop {
         let (steps, pt) = queue.pop_front().unwrap();
-        if pt == start_pos {
-            part1 = steps;
-            part2 = min(part2, steps);
-            break;
+        let height = map[&pt];
+        if found(&pt, height) {
+            return steps;
         }
 
         if !seen[&pt] {
-            let height = map[&pt];
-            if height == 0 {
-                part2 = min(part2, steps);
-            }
-
             if pt.x > 0 {
                 let neigh = pt.plus(-1, 0);
                 if !seen[&neigh] && map[&neigh] + 1 >= height {
@@ -91,20 +43,93 @@ pub fn day12(test_mode: bool, print: bool) {
             seen[&pt] = true;
         }
     }
+}
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    const DAY: u32 = 12;
+    const EXAMPLE: &'static str = TEST_EXAMPLE;
 
-    if print {
-        println!("Day {}.  Part 1: {}", DAY, part1);
+    type Parsed = (Grid<u8>, Pt, Pt);
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        let mut height = 0;
+        let mut cols = 0;
+        let mut start_pos = None;
+        let mut end_pos = None;
+        let mut data = Vec::with_capacity(2048);
+        let mut lines = lines(input);
+        for (i, mut line) in lines.by_ref().enumerate() {
+            // The first row sets the width.
+            if i == 0 {
+                cols = line.rest().len();
+            }
+            for j in 0..cols {
+                let at = line.clone();
+                let ch = line.char_where("a height from a to z, S or E", |ch| matches!(ch, 'a'..='z' | 'S' | 'E'))?;
+                let (pos, value) = match ch {
+                    'S' => (&mut start_pos, 0),
+                    'E' => (&mut end_pos, b'z' - b'a'),
+                    _ => {
+                        data.push(ch as u8 - b'a');
+                        continue;
+                    }
+                };
+                if pos.is_some() {
+                    return Err(at.error("only one S and one E"));
+                }
+                *pos = Some(Pt::at(j as i32, i as i32));
+                data.push(value);
+            }
+            line.end()?;
+            height = i + 1;
+        }
+        if height == 0 || cols == 0 {
+            return Err(lines.end_of_input("a heightmap"));
+        }
+        let (Some(start_pos), Some(end_pos)) = (start_pos, end_pos) else {
+            return Err(lines.end_of_input("a start S and an end E somewhere"));
+        };
+        Ok((
+            Grid {
+                rows: height,
+                cols,
+                data,
+            },
+            start_pos,
+            end_pos,
+        ))
     }
-    assert_eq!(part1, if test_mode { 31 } else { 394 });
 
-    if print {
-        println!("Day {}.  Part 2: {}", DAY, part2);
+    fn part1((map, start_pos, end_pos): &Self::Parsed) -> Answer {
+        steps_from_end(map, end_pos, |pt, _| pt == start_pos).into()
+    }
+
+    fn part2((map, _, end_pos): &Self::Parsed) -> Answer {
+        // The first low point reached is the closest, since it's a BFS.
+        steps_from_end(map, end_pos, |_, height| height == 0).into()
     }
-    assert_eq!(part2, if test_mode { 29 } else { 388 });
 }
 
-const TEST_EXAMPLE: &'static str = "Sabqponm
+const TEST_EXAMPLE: &str = "Sabqponm
 abcryxxl
 accszExk
 acctuvwj
 abdefghi";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::testing::{part1, part2};
+
+    #[test]
+    fn example_part1() {
+        assert_eq!(part1::<Day12>(TEST_EXAMPLE), 31.into());
+    }
+
+    #[test]
+    fn example_part2() {
+        assert_eq!(part2::<Day12>(TEST_EXAMPLE), 29.into());
+    }
+}
@@ -1,9 +1,10 @@
 use std::cmp::Ordering;
 
-const DAY: i32 = 13;
+use crate::solution::{Answer, Solution};
+use crate::utils::{lines, Line, ParseError};
 
 #[derive(Clone, Debug, Eq, PartialEq)]
-enum Elt {
+pub enum Elt {
     Num(i32),
     List(Vec<Elt>),
 }
@@ -14,52 +15,33 @@ impl PartialOrd for Elt {
     }
 }
 
-fn parse_list(input: &str) -> (&str, Vec<Elt>) {
-    let mut list = Vec::with_capacity(16);
-    let mut s = input;
-    loop {
-        if s.chars().next().unwrap() == ']' {
-            break;
-        }
-
-        let elt;
-        (s, elt) = parse_element(s);
-        list.push(elt);
-
-        if s.chars().next().unwrap() == ',' {
-            s = &s[1..];
-            // This parser allows trailing commas
-        }
-    }
-    (s, list)
-}
-
-fn parse_element(input: &str) -> (&str, Elt) {
-    match input.chars().next().unwrap() {
-        '[' => {
-            let (s, list) = parse_list(&input[1..]);
-            assert_eq!(s.chars().next().unwrap(), ']');
-            (&s[1..], Elt::List(list))
-        }
-        '0'..='9' => {
-            let mut not_numeric_idx = usize::MAX;
-            for (idx, ch) in input.char_indices() {
-                if ch < '0' || ch > '9' {
-                    not_numeric_idx = idx;
+// A number, or a list like "[1,[2,3]]".
+fn parse_element(line: &mut Line) -> Result<Elt, ParseError> {
+    if line.try_literal("[") {
+        let mut list = Vec::with_capacity(16);
+        if !line.try_literal("]") {
+            loop {
+                list.push(parse_element(line)?);
+                if line.try_literal("]") {
                     break;
                 }
+                if !line.try_literal(",") {
+                    return Err(line.error("\",\" or \"]\""));
+                }
             }
-            let num = input[..not_numeric_idx].parse().unwrap();
-            (&input[not_numeric_idx..], Elt::Num(num))
         }
-        _ => unreachable!(),
+        Ok(Elt::List(list))
+    } else if line.rest().starts_with(|ch: char| ch.is_ascii_digit()) {
+        Ok(Elt::Num(line.integer()?))
+    } else {
+        Err(line.error("a number or a list"))
     }
 }
 
-fn parse_packet(input: &str) -> Elt {
-    let (s, elt) = parse_element(input);
-    assert_eq!(s, "");
-    elt
+fn parse_packet(mut line: Line) -> Result<Elt, ParseError> {
+    let elt = parse_element(&mut line)?;
+    line.end()?;
+    Ok(elt)
 }
 
 impl Ord for Elt {
@@ -99,74 +81,71 @@ impl Ord for Elt {
     }
 }
 
-pub fn day13(test_mode: bool, print: bool) {
-    let file_str = std::fs::read_to_string(format!("inputs/input{:02}.txt", DAY)).unwrap();
-    let input_str = if test_mode {
-        TEST_EXAMPLE
-    } else {
-        &file_str.trim_end()
-    };
+pub struct Day13;
 
-    let packets = {
+impl Solution for Day13 {
+    const DAY: u32 = 13;
+    const EXAMPLE: &'static str = TEST_EXAMPLE;
+
+    type Parsed = Vec<(Elt, Elt)>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
         let mut packets = Vec::<(Elt, Elt)>::with_capacity(1024);
-        let mut lines = input_str.lines();
+        let mut lines = lines(input);
 
         loop {
-            let p1 = parse_packet(lines.next().unwrap());
-            let p2 = parse_packet(lines.next().unwrap());
+            let p1 = parse_packet(lines.next_line("a packet")?)?;
+            let p2 = parse_packet(lines.next_line("a packet")?)?;
             packets.push((p1, p2));
 
-            if lines.next() == None {
-                break;
+            match lines.next() {
+                Some(line) => line.end()?,
+                None => break,
             }
         }
 
-        packets
-    };
-
-    let mut part1 = 0;
-    for (i, (left, right)) in packets.iter().enumerate() {
-        // if left.cmp(right) == Ordering::Less {
-        //     part1 += i + 1;
-        // }
-        if left < right {
-            part1 += i + 1;
-        }
+        Ok(packets)
     }
 
-    if print {
-        println!("Day {}.  Part 1: {}", DAY, part1);
+    fn part1(packets: &Self::Parsed) -> Answer {
+        let mut part1 = 0;
+        for (i, (left, right)) in packets.iter().enumerate() {
+            // if left.cmp(right) == Ordering::Less {
+            //     part1 += i + 1;
+            // }
+            if left < right {
+                part1 += i + 1;
+            }
+        }
+        part1.into()
     }
-    assert_eq!(part1, if test_mode { 13 } else { 5252 });
 
-    let mut all_packets = Vec::with_capacity(2 + 2 * packets.len());
-    for (left, right) in packets {
-        all_packets.push(left);
-        all_packets.push(right);
-    }
+    fn part2(packets: &Self::Parsed) -> Answer {
+        let mut all_packets = Vec::with_capacity(2 + 2 * packets.len());
+        for (left, right) in packets {
+            all_packets.push(left);
+            all_packets.push(right);
+        }
 
-    let div1 = Elt::List(vec![Elt::List(vec![Elt::Num(2)])]);
-    let div2 = Elt::List(vec![Elt::List(vec![Elt::Num(6)])]);
+        let div1 = Elt::List(vec![Elt::List(vec![Elt::Num(2)])]);
+        let div2 = Elt::List(vec![Elt::List(vec![Elt::Num(6)])]);
 
-    all_packets.push(div1.clone());
-    all_packets.push(div2.clone());
+        all_packets.push(&div1);
+        all_packets.push(&div2);
 
-    all_packets.sort();
+        all_packets.sort();
 
-    let mut part2 = 1;
-    for (i, pack) in all_packets.iter().enumerate() {
-        if pack == &div1 || pack == &div2 {
-            part2 *= i + 1;
+        let mut part2 = 1;
+        for (i, pack) in all_packets.iter().enumerate() {
+            if *pack == &div1 || *pack == &div2 {
+                part2 *= i + 1;
+            }
         }
+        part2.into()
     }
-
-    if print {
-        println!("Day {}.  Part 2: {}", DAY, part2);
-    }
-    assert_eq!(part2, if test_mode { 140 } else { 20592 });
 }
 
-const TEST_EXAMPLE: &'static str = "[1,1,3,1,1]
+const TEST_EXAMPLE: &str = "[1,1,3,1,1]
 [1,1,5,1,1]
 
 [[1],[2,3,4]]
@@ -189,3 +168,19 @@ const TEST_EXAMPLE: &'static str = "[1,1,3,1,1]
 
 [1,[2,[3,[4,[5,6,7]]]],8,9]
 [1,[2,[3,[4,[5,6,0]]]],8,9]";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::testing::{part1, part2};
+
+    #[test]
+    fn example_part1() {
+        assert_eq!(part1::<Day13>(TEST_EXAMPLE), 13.into());
+    }
+
+    #[test]
+    fn example_part2() {
+        assert_eq!(part2::<Day13>(TEST_EXAMPLE), 140.into());
+    }
+}
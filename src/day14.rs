@@ -1,9 +1,20 @@
 use itertools::Itertools;
 use std::cmp::max;
 
-use crate::utils::Grid;
-
-const DAY: i32 = 14;
+use crate::solution::{Answer, Solution};
+use crate::utils::{lines, Grid, Line, ParseError};
+
+// "498,4", where y is the depth below the sand's source.
+fn parse_point(line: &mut Line) -> Result<(i32, i32), ParseError> {
+    let x = line.integer()?;
+    line.literal(",")?;
+    let at = line.clone();
+    let y = line.integer()?;
+    if y < 0 {
+        return Err(at.error("a depth of 0 or more"));
+    }
+    Ok((x, y))
+}
 
 #[inline]
 fn lohi<T: PartialOrd>(a: T, b: T) -> (T, T) {
@@ -14,82 +25,16 @@ fn lohi<T: PartialOrd>(a: T, b: T) -> (T, T) {
     }
 }
 
-pub fn day14(test_mode: bool, print: bool) {
-    let file_str = std::fs::read_to_string(format!("inputs/input{:02}.txt", DAY)).unwrap();
-    let input_str = if test_mode {
-        TEST_EXAMPLE
-    } else {
-        &file_str.trim_end()
-    };
-
-    let rocks = {
-        let mut rocks: Vec<Vec<(i32, i32)>> = Vec::with_capacity(64);
-
-        for line in input_str.lines() {
-            let path = line
-                .split(" -> ")
-                .map(|coord_str| {
-                    let mut pieces = coord_str.split(",");
-                    let x = pieces.next().unwrap().parse().unwrap();
-                    let y = pieces.next().unwrap().parse().unwrap();
-                    (x, y)
-                })
-                .collect();
-
-            rocks.push(path);
-        }
-        rocks
-    };
-
-    let y_max = {
-        let mut y_max = i32::MIN;
-        for rock in &rocks {
-            for pt in rock {
-                y_max = max(y_max, pt.1);
-            }
-        }
-        y_max
-    };
-
-    let x0 = 500 - y_max - 3;
-
-    let mut grid = Grid::<u8>::filled((y_max + 3) as usize, (2 * (y_max + 3)) as usize, 0);
-
-    for rock in &rocks {
-        for (p1, p2) in rock.iter().tuple_windows() {
-            if p1.0 == p2.0 {
-                // Along Y
-                let (y_lo, y_hi) = lohi(p1.1, p2.1);
-                for y in y_lo..=y_hi {
-                    grid[(y as usize, (p1.0 - x0) as usize)] = 1;
-                }
-            } else {
-                // Along X
-                let (x_lo, x_hi) = lohi(p1.0, p2.0);
-                for x in x_lo..=x_hi {
-                    grid[(p1.1 as usize, (x - x0) as usize)] = 1;
-                }
-            }
-        }
-    }
-
-    // Line along the bottom
-    let rows = grid.rows;
-    for c in 0..grid.cols {
-        grid[(rows - 1, c)] = 1;
-    }
-
-    let drop_col = (500 - x0) as usize;
-
+// Drops sand until it blocks the source, or (for `until_floor`) until a grain
+// first lands on the floor. Returns the grains that came to rest before that.
+fn pour(grid: &Grid<u8>, drop_col: usize, until_floor: bool) -> usize {
+    let mut grid = grid.clone();
     let mut grains = 0;
-    let mut part1 = 0;
-    let mut part2 = 0;
     'outer: loop {
         let mut sand_r = 0usize;
         let mut sand_c = drop_col;
 
         if grid[(sand_r, sand_c)] > 0 {
-            part2 = grains;
             break;
         }
 
@@ -106,8 +51,8 @@ pub fn day14(test_mode: bool, print: bool) {
                 // Blocked
                 grid[(sand_r, sand_c)] = 2;
 
-                if part1 == 0 && sand_r == grid.rows - 2 {
-                    part1 = grains;
+                if until_floor && sand_r == grid.rows - 2 {
+                    break 'outer;
                 }
                 grains += 1;
                 break;
@@ -118,17 +63,110 @@ pub fn day14(test_mode: bool, print: bool) {
             }
         }
     }
+    grains
+}
+
+pub struct Day14;
+
+impl Solution for Day14 {
+    const DAY: u32 = 14;
+    const EXAMPLE: &'static str = TEST_EXAMPLE;
+
+    // The cave, and the column the sand drops from.
+    type Parsed = (Grid<u8>, usize);
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        let mut rocks: Vec<Vec<(i32, i32)>> = Vec::with_capacity(64);
+
+        let mut lines = lines(input);
+        for mut line in lines.by_ref() {
+            let mut path = vec![parse_point(&mut line)?];
+            while line.try_literal(" -> ") {
+                let at = line.clone();
+                let pt = parse_point(&mut line)?;
+                let prev = path.last().unwrap();
+                if pt.0 != prev.0 && pt.1 != prev.1 {
+                    return Err(at.error(format!("a point in line with {},{}", prev.0, prev.1)));
+                }
+                path.push(pt);
+            }
+            line.end()?;
+
+            rocks.push(path);
+        }
+        if rocks.is_empty() {
+            return Err(lines.end_of_input("a path of rock"));
+        }
+
+        let y_max = {
+            let mut y_max = i32::MIN;
+            for rock in &rocks {
+                for pt in rock {
+                    y_max = max(y_max, pt.1);
+                }
+            }
+            y_max
+        };
+
+        let x0 = 500 - y_max - 3;
+
+        let mut grid = Grid::<u8>::filled((y_max + 3) as usize, (2 * (y_max + 3)) as usize, 0);
+
+        for rock in &rocks {
+            for (p1, p2) in rock.iter().tuple_windows() {
+                if p1.0 == p2.0 {
+                    // Along Y
+                    // Rock further out than the sand can spread doesn't matter.
+                    if !(x0..x0 + grid.cols as i32).contains(&p1.0) {
+                        continue;
+                    }
+                    let (y_lo, y_hi) = lohi(p1.1, p2.1);
+                    for y in y_lo..=y_hi {
+                        grid[(y as usize, (p1.0 - x0) as usize)] = 1;
+                    }
+                } else {
+                    // Along X
+                    let (x_lo, x_hi) = lohi(p1.0, p2.0);
+                    for x in x_lo.max(x0)..=x_hi.min(x0 + grid.cols as i32 - 1) {
+                        grid[(p1.1 as usize, (x - x0) as usize)] = 1;
+                    }
+                }
+            }
+        }
+
+        // Line along the bottom
+        let rows = grid.rows;
+        for c in 0..grid.cols {
+            grid[(rows - 1, c)] = 1;
+        }
+
+        Ok((grid, (500 - x0) as usize))
+    }
 
-    if print {
-        println!("Day {}.  Part 1: {}", DAY, part1);
+    fn part1((grid, drop_col): &Self::Parsed) -> Answer {
+        pour(grid, *drop_col, true).into()
     }
-    assert_eq!(part1, if test_mode { 24 } else { 979 });
 
-    if print {
-        println!("Day {}.  Part 2: {}", DAY, part2);
+    fn part2((grid, drop_col): &Self::Parsed) -> Answer {
+        pour(grid, *drop_col, false).into()
     }
-    assert_eq!(part2, if test_mode { 93 } else { 29044 });
 }
 
-const TEST_EXAMPLE: &'static str = "498,4 -> 498,6 -> 496,6
+const TEST_EXAMPLE: &str = "498,4 -> 498,6 -> 496,6
 503,4 -> 502,4 -> 502,9 -> 494,9";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::testing::{part1, part2};
+
+    #[test]
+    fn example_part1() {
+        assert_eq!(part1::<Day14>(TEST_EXAMPLE), 24.into());
+    }
+
+    #[test]
+    fn example_part2() {
+        assert_eq!(part2::<Day14>(TEST_EXAMPLE), 93.into());
+    }
+}
@@ -2,9 +2,8 @@ use std::cmp::max;
 
 use itertools::Itertools;
 
-use crate::utils::Pt;
-
-const DAY: i32 = 15;
+use crate::solution::{Answer, Solution};
+use crate::utils::{lines, lint_lines, Line, ParseError, Pt};
 
 // Inclusive
 fn empty_at_y(m: &(Pt, Pt), y: i32) -> Option<(i32, i32)> {
@@ -13,7 +12,7 @@ fn empty_at_y(m: &(Pt, Pt), y: i32) -> Option<(i32, i32)> {
     let mdist = sensor.l1_to(beacon);
 
     let r = mdist - (y - sensor.y).abs();
-    if r <= 0 {
+    if r < 0 || (r == 0 && y == beacon.y) {
         return None;
     }
 
@@ -30,7 +29,7 @@ fn empty_at_y(m: &(Pt, Pt), y: i32) -> Option<(i32, i32)> {
     }
 }
 
-fn find_unseen(sensed: &Vec<(Pt, i32)>, lo: &Pt, hi: &Pt) -> Option<Pt> {
+fn find_unseen(sensed: &[(Pt, i32)], lo: &Pt, hi: &Pt) -> Option<Pt> {
     // println!("Searching [{}..{}], [{}..{}]", lo.x, hi.x, lo.y, hi.y);
     for (sensor, l1) in sensed {
         if sensor.l1_to_coords(lo.x, lo.y) <= *l1
@@ -60,67 +59,149 @@ fn find_unseen(sensed: &Vec<(Pt, i32)>, lo: &Pt, hi: &Pt) -> Option<Pt> {
     }
 }
 
-pub fn day15(test_mode: bool, print: bool) {
-    let file_str = std::fs::read_to_string(format!("inputs/input{:02}.txt", DAY)).unwrap();
-    let input_str = if test_mode {
-        TEST_EXAMPLE
-    } else {
-        &file_str.trim_end()
-    };
-
-    let re = regex::Regex::new(
-        r"Sensor at x=(-?\d+), y=(-?\d+): closest beacon is at x=(-?\d+), y=(-?\d+)",
-    )
-    .unwrap();
-
-    let measurements = {
-        let mut measurements = Vec::<(Pt, Pt)>::with_capacity(64);
-        for line in input_str.lines() {
-            let caps = re.captures(line).unwrap();
-            measurements.push((
-                Pt::at(caps[1].parse().unwrap(), caps[2].parse().unwrap()),
-                Pt::at(caps[3].parse().unwrap(), caps[4].parse().unwrap()),
-            ));
+pub struct Survey {
+    measurements: Vec<(Pt, Pt)>,
+    probe_y: i32,
+    limit: i32,
+}
+
+// "Sensor at x=2, y=18: closest beacon is at x=-2, y=15"
+fn parse_measurement(mut line: Line) -> Result<(Pt, Pt), ParseError> {
+    line.literal("Sensor at x=")?;
+    let sensor_x = line.integer()?;
+    line.literal(", y=")?;
+    let sensor = Pt::at(sensor_x, line.integer()?);
+    line.literal(": closest beacon is at x=")?;
+    let beacon_x = line.integer()?;
+    line.literal(", y=")?;
+    let beacon = Pt::at(beacon_x, line.integer()?);
+    line.end()?;
+    Ok((sensor, beacon))
+}
+
+fn parse_measurements(input: &str) -> Result<Vec<(Pt, Pt)>, ParseError> {
+    lines(input).map(parse_measurement).collect()
+}
+
+// The puzzle promises each sensor's beacon is the only closest one. For part
+// 1, that means no sensor reaches a beacon on the probed row but its own.
+fn check_probed_beacons(survey: &Survey) {
+    for (_, beacon) in survey.measurements.iter().filter(|(_, beacon)| beacon.y == survey.probe_y) {
+        for (sensor, own) in &survey.measurements {
+            assert!(
+                own == beacon || sensor.l1_to(beacon) > sensor.l1_to(own),
+                "the sensor at {},{} is as close to another beacon",
+                sensor.x,
+                sensor.y
+            );
         }
-        measurements
-    };
+    }
+}
+
+pub struct Day15;
+
+impl Solution for Day15 {
+    const DAY: u32 = 15;
+    const EXAMPLE: &'static str = TEST_EXAMPLE;
+
+    type Parsed = Survey;
 
-    let probe_y = if test_mode { 10 } else { 2000000 };
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        Ok(Survey {
+            measurements: parse_measurements(input)?,
+            probe_y: 2000000,
+            limit: 4000000,
+        })
+    }
 
-    let empty_ranges: Vec<(i32, i32)> = measurements
-        .iter()
-        .filter_map(|m| empty_at_y(m, probe_y))
-        .sorted()
-        .collect();
+    fn parse_example(input: &str) -> Result<Self::Parsed, ParseError> {
+        Ok(Survey {
+            measurements: parse_measurements(input)?,
+            probe_y: 10,
+            limit: 20,
+        })
+    }
 
-    let mut top = i32::MIN;
-    let mut part1 = 0;
-    for (lo, hi) in empty_ranges {
-        part1 += hi - max(lo, top) + 1;
-        top = hi + 1;
+    fn lint(input: &str) -> Vec<ParseError> {
+        lint_lines(input, parse_measurement)
     }
 
-    if print {
-        println!("Day {}.  Part 1: {}", DAY, part1);
+    fn part1(survey: &Self::Parsed) -> Answer {
+        let empty_ranges: Vec<(i32, i32)> = survey
+            .measurements
+            .iter()
+            .filter_map(|m| empty_at_y(m, survey.probe_y))
+            .sorted()
+            .collect();
+
+        let mut top = i32::MIN;
+        let mut part1 = 0;
+        for (lo, hi) in empty_ranges {
+            // Ranges can sit inside earlier ones, so only count past the top.
+            part1 += max(hi - max(lo, top) + 1, 0);
+            top = max(top, hi + 1);
+        }
+        part1.into()
     }
-    assert_eq!(part1, if test_mode { 26 } else { 4961647 });
 
-    let limit = if test_mode { 20 } else { 4000000 };
+    fn part2(survey: &Self::Parsed) -> Answer {
+        let limit = survey.limit;
+        let sensed: Vec<_> = survey
+            .measurements
+            .iter()
+            .map(|(s, b)| (s.clone(), s.l1_to(b)))
+            .collect();
+        let distress = find_unseen(&sensed, &Pt::at(0, 0), &Pt::at(limit, limit)).unwrap();
+        let part2 = 4000000 * (distress.x as usize) + distress.y as usize;
+        part2.into()
+    }
 
-    let sensed: Vec<_> = measurements
-        .iter()
-        .map(|(s, b)| (s.clone(), s.l1_to(b)))
-        .collect();
-    let distress = find_unseen(&sensed, &Pt::at(0, 0), &Pt::at(limit, limit)).unwrap();
-    let part2 = 4000000 * (distress.x as usize) + distress.y as usize;
+    // Every spot on the row, one at a time.
+    fn reference1(survey: &Self::Parsed) -> Option<Answer> {
+        check_probed_beacons(survey);
+        let y = survey.probe_y;
+        let reaches: Vec<(&Pt, i32)> = survey.measurements.iter().map(|(s, b)| (s, s.l1_to(b))).collect();
+        let lo = reaches.iter().map(|(s, reach)| s.x - reach).min().unwrap();
+        let hi = reaches.iter().map(|(s, reach)| s.x + reach).max().unwrap();
+        let part1 = (lo..=hi)
+            .filter(|&x| reaches.iter().any(|(s, reach)| s.l1_to_coords(x, y) <= *reach))
+            .filter(|&x| !survey.measurements.iter().any(|(_, b)| *b == Pt::at(x, y)))
+            .count();
+        Some(part1.into())
+    }
 
-    if print {
-        println!("Day {}.  Part 2: {}", DAY, part2);
+    // Every row, as a list of where each sensor reaches on it.
+    fn reference2(survey: &Self::Parsed) -> Option<Answer> {
+        let limit = survey.limit;
+        let mut unseen = Vec::new();
+        for y in 0..=limit {
+            let spans: Vec<(i32, i32)> = survey
+                .measurements
+                .iter()
+                .filter_map(|(s, b)| {
+                    let half = s.l1_to(b) - (s.y - y).abs();
+                    (half >= 0).then_some((s.x - half, s.x + half))
+                })
+                .sorted()
+                .collect();
+            let mut x = 0;
+            for (lo, hi) in spans {
+                while x < lo && x <= limit && unseen.len() < 2 {
+                    unseen.push(Pt::at(x, y));
+                    x += 1;
+                }
+                x = max(x, hi + 1);
+            }
+            unseen.extend((x..=limit).take(2).map(|x| Pt::at(x, y)));
+            assert!(unseen.len() <= 1, "more than one spot for the distress beacon");
+        }
+        assert!(unseen.len() == 1, "nowhere for the distress beacon");
+        let part2 = 4000000 * (unseen[0].x as usize) + unseen[0].y as usize;
+        Some(part2.into())
     }
-    assert_eq!(part2, if test_mode { 56000011 } else { 12274327017867 });
 }
 
-const TEST_EXAMPLE: &'static str = "Sensor at x=2, y=18: closest beacon is at x=-2, y=15
+const TEST_EXAMPLE: &str = "Sensor at x=2, y=18: closest beacon is at x=-2, y=15
 Sensor at x=9, y=16: closest beacon is at x=10, y=16
 Sensor at x=13, y=2: closest beacon is at x=15, y=3
 Sensor at x=12, y=14: closest beacon is at x=10, y=16
@@ -134,3 +215,31 @@ Sensor at x=17, y=20: closest beacon is at x=21, y=22
 Sensor at x=16, y=7: closest beacon is at x=15, y=3
 Sensor at x=14, y=3: closest beacon is at x=15, y=3
 Sensor at x=20, y=1: closest beacon is at x=15, y=3";
+
+// One sensor's range on the probed row inside another's, and one that only
+// just reaches the row.
+#[cfg(test)]
+const ROW_EDGES_EXAMPLE: &str = "Sensor at x=8, y=8: closest beacon is at x=0, y=5
+Sensor at x=8, y=1: closest beacon is at x=7, y=15
+Sensor at x=30, y=7: closest beacon is at x=30, y=4";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::testing::{part1, part2};
+
+    #[test]
+    fn example_part1() {
+        assert_eq!(part1::<Day15>(TEST_EXAMPLE), 26.into());
+    }
+
+    #[test]
+    fn example_part2() {
+        assert_eq!(part2::<Day15>(TEST_EXAMPLE), 56000011.into());
+    }
+
+    #[test]
+    fn row_edges_example_part1() {
+        assert_eq!(part1::<Day15>(ROW_EDGES_EXAMPLE), 20.into());
+    }
+}
@@ -3,17 +3,52 @@ use std::collections::BinaryHeap;
 use itertools::Itertools;
 use rustc_hash::{FxHashMap, FxHashSet};
 
-use crate::utils::ByFirst;
-
-const DAY: i32 = 16;
+use crate::solution::{Answer, Solution};
+use crate::utils::{lines, ByFirst, Line, Lines, ParseError};
 
 #[derive(Debug)]
 struct Room {
-    name: String,
     flow: i32,
     tunnels: Vec<String>,
 }
 
+// "Valve AA has flow rate=0; tunnels lead to valves DD, II, BB". Where each
+// tunnel was named goes in `tunnel_ats`, to check later that they all lead
+// somewhere.
+fn parse_valve<'a>(mut line: Line<'a>, tunnel_ats: &mut Vec<Line<'a>>) -> Result<(&'a str, Room), ParseError> {
+    line.literal("Valve ")?;
+    let name = line.word("a valve name")?;
+    line.literal(" has flow rate=")?;
+    let flow = line.integer()?;
+    line.one_of(&[("; tunnels lead to valves ", ()), ("; tunnel leads to valve ", ())])?;
+    let mut tunnels = Vec::new();
+    let mut ats = Vec::new();
+    loop {
+        ats.push(line.clone());
+        tunnels.push(line.word("a valve name")?.to_string());
+        if !line.try_literal(", ") {
+            break;
+        }
+    }
+    line.end()?;
+    tunnel_ats.extend(ats);
+    Ok((name, Room { flow, tunnels }))
+}
+
+// Tunnels to valves that aren't in the scan, and a missing AA. `lines` is
+// what was scanned, for where the input ended.
+fn check_scan(has_valve: impl Fn(&str) -> bool, tunnel_ats: &[Line], lines: &Lines) -> Vec<ParseError> {
+    let mut errors: Vec<ParseError> = tunnel_ats
+        .iter()
+        .filter(|at| !has_valve(Line::clone(at).word("a valve name").unwrap()))
+        .map(|at| at.error("a valve that's in the scan"))
+        .collect();
+    if !has_valve("AA") {
+        errors.push(lines.end_of_input("valve AA, where we start"));
+    }
+    errors
+}
+
 fn compute_optimistic_flow(
     rooms: &FxHashMap<String, Room>,
     remain: &FxHashSet<&String>,
@@ -72,277 +107,368 @@ fn compute_optimistic_flow_with_friend(
     optimistic
 }
 
-pub fn day16(test_mode: bool, print: bool) {
-    let file_str = std::fs::read_to_string(format!("inputs/input{:02}.txt", DAY)).unwrap();
-    let input_str = if test_mode {
-        TEST_EXAMPLE
-    } else {
-        &file_str.trim_end()
-    };
-
-    let re =
-        regex::Regex::new(r"Valve ([^ ]+) has flow rate=(\d+); tunnels? leads? to valves? (.*)")
-            .unwrap();
-
-    let mut rooms = FxHashMap::with_capacity_and_hasher(128, Default::default());
-    for line in input_str.lines() {
-        let caps = re.captures(line).unwrap();
-        let tunnels = caps[3].split(", ").map(|s| s.to_string()).collect();
-
-        let name: String = caps[1].into();
-        rooms.insert(
-            name.clone(),
-            Room {
-                name,
-                flow: caps[2].parse().unwrap(),
-                tunnels,
-            },
-        );
-    }
-    let rooms = rooms;
-
-    // println!("Rooms: {:?}", rooms);
-
-    // All paths
-    let mut dist = FxHashMap::<(String, String), i32>::with_capacity_and_hasher(
-        rooms.len() * rooms.len(),
-        Default::default(),
-    );
-    for (a, a_room) in rooms.iter() {
-        dist.insert((a.clone(), a.clone()), 0);
-        for (b, _) in rooms.iter() {
-            if a != b {
-                if a_room.tunnels.iter().find(|dest| *dest == b) == None {
-                    dist.insert((a.clone(), b.clone()), i32::MAX / 2);
-                    dist.insert((b.clone(), a.clone()), i32::MAX / 2);
-                } else {
-                    dist.insert((a.clone(), b.clone()), 1);
-                    dist.insert((b.clone(), a.clone()), 1);
-                }
-            }
+// For the reference versions of the parts: tries every order of opening the
+// valves that fits in the time, from `at` with `time_left`, and keeps the most
+// released for each set of valves opened (whether each of `valves` is open).
+fn open_every_way(
+    cave: &Cave,
+    valves: &[&String],
+    at: &str,
+    time_left: i32,
+    opened: &mut Vec<bool>,
+    released: i32,
+    best: &mut FxHashMap<Vec<bool>, i32>,
+) {
+    match best.get_mut(opened) {
+        Some(most) => *most = (*most).max(released),
+        None => {
+            best.insert(opened.clone(), released);
         }
     }
-
-    for (r, _) in rooms.iter() {
-        for (u, _) in rooms.iter() {
-            for (v, _) in rooms.iter() {
-                // TODO: string copies
-                let longcut = *dist.get(&(u.to_string(), v.to_string())).unwrap();
-                let shortcut = dist.get(&(u.to_string(), r.to_string())).unwrap()
-                    + dist.get(&(r.to_string(), v.to_string())).unwrap();
-                if shortcut < longcut {
-                    dist.insert((u.to_string(), v.to_string()), shortcut);
-                    dist.insert((v.to_string(), u.to_string()), shortcut);
-                }
-            }
+    for (i, valve) in valves.iter().enumerate() {
+        let left = time_left - cave.dist[&(at.to_string(), (*valve).clone())] - 1;
+        if !opened[i] && left > 0 {
+            let flow = cave.rooms[*valve].flow;
+            opened[i] = true;
+            open_every_way(cave, valves, valve, left, opened, released + left * flow, best);
+            opened[i] = false;
         }
     }
+}
 
-    let matter = rooms
-        .iter()
-        .filter_map(|(s, r)| if r.flow > 0 { Some(s) } else { None })
-        .collect::<FxHashSet<_>>();
-    // println!("Matter: {:?}", matter);
+// The most released for each set of valves opened, in `time` minutes.
+fn best_by_valves(cave: &Cave, time: i32) -> FxHashMap<Vec<bool>, i32> {
+    let valves: Vec<&String> = cave.rooms.iter().filter(|(_, room)| room.flow > 0).map(|(name, _)| name).sorted().collect();
+    let mut best = FxHashMap::default();
+    open_every_way(cave, &valves, "AA", time, &mut vec![false; valves.len()], 0, &mut best);
+    best
+}
 
+// Valves, and the shortest distances between every pair of them.
+pub struct Cave {
+    rooms: FxHashMap<String, Room>,
+    dist: FxHashMap<(String, String), i32>,
+}
 
-    #[derive(Debug)]
-    struct Remember<'a> {
-        room: String,
-        time_left: i32,
-        sofar: i32,
-        rooms_left: FxHashSet<&'a String>,
+pub struct Day16;
 
-        followed: Vec<String>, // TODO: delete me
-    }
-    let mut heap: BinaryHeap<ByFirst<(i32, Remember)>> = BinaryHeap::new();
-    heap.push(ByFirst((
-        i32::MAX,
-        Remember {
-            room: "AA".to_string(),
-            time_left: 30,
-            sofar: 0,
-            rooms_left: matter.clone(),
-            followed: Vec::new(),
-        },
-    )));
-
-    let mut best_sofar = 0;
-    loop {
-        let ByFirst((_theoretical, at)) = heap.pop().unwrap();
+impl Solution for Day16 {
+    const DAY: u32 = 16;
+    const EXAMPLE: &'static str = TEST_EXAMPLE;
 
-        let mut next_followed = at.followed.clone();
-        next_followed.push(at.room.clone());
+    type Parsed = Cave;
 
-        if at.sofar > best_sofar {
-            best_sofar = at.sofar;
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        let mut rooms = FxHashMap::with_capacity_and_hasher(128, Default::default());
+        // Where each tunnel was named, to check they all lead somewhere.
+        let mut tunnel_ats = Vec::with_capacity(256);
+        let mut lines = lines(input);
+        for line in lines.by_ref() {
+            let (name, room) = parse_valve(line, &mut tunnel_ats)?;
+            rooms.insert(name.to_string(), room);
+        }
+        if let Some(err) = check_scan(|name| rooms.contains_key(name), &tunnel_ats, &lines).into_iter().next() {
+            return Err(err);
         }
 
-        if at.rooms_left.is_empty() || at.time_left <= 2 {
-            break;
+        // println!("Rooms: {:?}", rooms);
+
+        // All paths
+        let mut dist = FxHashMap::<(String, String), i32>::with_capacity_and_hasher(
+            rooms.len() * rooms.len(),
+            Default::default(),
+        );
+        for (a, a_room) in rooms.iter() {
+            dist.insert((a.clone(), a.clone()), 0);
+            for (b, _) in rooms.iter() {
+                if a != b {
+                    if !a_room.tunnels.iter().any(|dest| dest == b) {
+                        dist.insert((a.clone(), b.clone()), i32::MAX / 2);
+                        dist.insert((b.clone(), a.clone()), i32::MAX / 2);
+                    } else {
+                        dist.insert((a.clone(), b.clone()), 1);
+                        dist.insert((b.clone(), a.clone()), 1);
+                    }
+                }
+            }
         }
 
-        for next in &at.rooms_left {
-            let mut next_left = at.rooms_left.clone();
-            next_left.remove(next);
-
-            let time_left_after_move =
-                at.time_left - dist.get(&(at.room.clone(), (*next).clone())).unwrap();
-            if time_left_after_move > 2 {
-                let time_left_after_open = time_left_after_move - 1;
-                let next_sofar = at.sofar + time_left_after_open * rooms.get(*next).unwrap().flow;
-
-                let theoretical_flow = next_sofar
-                    + compute_optimistic_flow(&rooms, &next_left, time_left_after_open);
-
-                heap.push(ByFirst((
-                    theoretical_flow,
-                    Remember {
-                        room: (*next).clone(),
-                        time_left: time_left_after_open,
-                        sofar: next_sofar,
-                        rooms_left: next_left,
-                        followed: next_followed.clone(),
-                    },
-                )));
+        for (r, _) in rooms.iter() {
+            for (u, _) in rooms.iter() {
+                for (v, _) in rooms.iter() {
+                    // TODO: string copies
+                    let longcut = *dist.get(&(u.to_string(), v.to_string())).unwrap();
+                    let shortcut = dist.get(&(u.to_string(), r.to_string())).unwrap()
+                        + dist.get(&(r.to_string(), v.to_string())).unwrap();
+                    if shortcut < longcut {
+                        dist.insert((u.to_string(), v.to_string()), shortcut);
+                        dist.insert((v.to_string(), u.to_string()), shortcut);
+                    }
+                }
             }
         }
-    }
 
-    let part1 = best_sofar;
-    if print {
-        println!("Day {}.  Part 1: {}", DAY, part1);
+        Ok(Cave { rooms, dist })
     }
-    assert_eq!(part1, if test_mode { 1651 } else { 2330 });
 
-    // Part 2, with an elephant
-
-    #[derive(Debug)]
-    struct Remember2<'a> {
-        me_at: String,
-        me_time_left: i32,
-
-        elph_at: String,
-        elph_time_left: i32,
-
-        sofar: i32, // Flow released so far.
-        rooms_left: FxHashSet<&'a String>,
-
-        me_followed: Vec<String>, // TODO: delete me
-        elph_followed: Vec<String>,
+    fn lint(input: &str) -> Vec<ParseError> {
+        let mut errors = Vec::new();
+        // Every valve named at the start of a line, even on lines with
+        // problems, so tunnels to those aren't reported too.
+        let mut names = FxHashSet::default();
+        let mut tunnel_ats = Vec::new();
+        let mut lines = lines(input);
+        for line in lines.by_ref() {
+            let mut start = line.clone();
+            if start.try_literal("Valve ") {
+                names.insert(start.word("a valve name").unwrap_or(""));
+            }
+            if let Err(err) = parse_valve(line, &mut tunnel_ats) {
+                errors.push(err);
+            }
+        }
+        errors.extend(check_scan(|name| names.contains(name), &tunnel_ats, &lines));
+        errors
     }
 
-    let mut heap: BinaryHeap<ByFirst<(i32, Remember2)>> = BinaryHeap::new();
-    heap.push(ByFirst((
-        i32::MAX,
-        Remember2 {
-            me_at: "AA".to_string(),
-            me_time_left: 26,
-            elph_at: "AA".to_string(),
-            elph_time_left: 26,
-            sofar: 0,
-            rooms_left: matter.clone(),
-            me_followed: Vec::new(),
-            elph_followed: Vec::new(),
-        },
-    )));
-
-    let mut best_sofar = 0;
-    loop {
-        let ByFirst((_theoretical, at)) = heap.pop().unwrap();
-
-        if at.sofar > best_sofar {
-            best_sofar = at.sofar;
+    fn part1(Cave { rooms, dist }: &Self::Parsed) -> Answer {
+        let matter = rooms
+            .iter()
+            .filter_map(|(s, r)| if r.flow > 0 { Some(s) } else { None })
+            .collect::<FxHashSet<_>>();
+        // println!("Matter: {:?}", matter);
+
+        #[derive(Debug)]
+        struct Remember<'a> {
+            room: String,
+            time_left: i32,
+            sofar: i32,
+            rooms_left: FxHashSet<&'a String>,
+
+            followed: Vec<String>, // TODO: delete me
         }
+        let mut heap: BinaryHeap<ByFirst<(i32, Remember)>> = BinaryHeap::new();
+        heap.push(ByFirst((
+            i32::MAX,
+            Remember {
+                room: "AA".to_string(),
+                time_left: 30,
+                sofar: 0,
+                rooms_left: matter.clone(),
+                followed: Vec::new(),
+            },
+        )));
 
-        if at.rooms_left.is_empty() || (at.me_time_left <= 2 && at.elph_time_left <= 2) {
-            break;
-        }
+        let mut best_sofar = 0;
+        while let Some(ByFirst((_theoretical, at))) = heap.pop() {
+            let mut next_followed = at.followed.clone();
+            next_followed.push(at.room.clone());
+
+            if at.sofar > best_sofar {
+                best_sofar = at.sofar;
+            }
 
-        for next in &at.rooms_left {
-            let mut next_left = at.rooms_left.clone();
-            next_left.remove(next);
+            if at.rooms_left.is_empty() || at.time_left <= 2 {
+                break;
+            }
 
-            if at.me_time_left >= at.elph_time_left {
-                // I move
-                let mut followed = at.me_followed.clone();
-                followed.push((*next).clone());
+            for next in &at.rooms_left {
+                let mut next_left = at.rooms_left.clone();
+                next_left.remove(next);
 
                 let time_left_after_move =
-                    at.me_time_left - dist.get(&(at.me_at.clone(), (*next).clone())).unwrap();
-                if time_left_after_move > 2 {
+                    at.time_left - dist.get(&(at.room.clone(), (*next).clone())).unwrap();
+                if time_left_after_move > 1 {
                     let time_left_after_open = time_left_after_move - 1;
-                    let next_sofar =
-                        at.sofar + time_left_after_open * rooms.get(*next).unwrap().flow;
+                    let next_sofar = at.sofar + time_left_after_open * rooms.get(*next).unwrap().flow;
 
                     let theoretical_flow = next_sofar
-                        + compute_optimistic_flow_with_friend(
-                            &rooms,
-                            &next_left,
-                            time_left_after_open,
-                            at.elph_time_left,
-                        );
+                        + compute_optimistic_flow(rooms, &next_left, time_left_after_open);
 
                     heap.push(ByFirst((
                         theoretical_flow,
-                        Remember2 {
-                            me_at: (*next).clone(),
-                            me_time_left: time_left_after_open,
-                            elph_at: at.elph_at.clone(),
-                            elph_time_left: at.elph_time_left,
+                        Remember {
+                            room: (*next).clone(),
+                            time_left: time_left_after_open,
                             sofar: next_sofar,
                             rooms_left: next_left,
-
-                            me_followed: followed,
-                            elph_followed: at.elph_followed.clone(),
+                            followed: next_followed.clone(),
                         },
                     )));
                 }
-            } else {
-                // Elephant moves
-                let mut followed = at.elph_followed.clone();
-                followed.push((*next).clone());
+            }
+        }
 
-                let time_left_after_move =
-                    at.elph_time_left - dist.get(&(at.elph_at.clone(), (*next).clone())).unwrap();
-                if time_left_after_move > 2 {
-                    let time_left_after_open = time_left_after_move - 1;
-                    let next_sofar =
-                        at.sofar + time_left_after_open * rooms.get(*next).unwrap().flow;
+        best_sofar.into()
+    }
 
-                    let theoretical_flow = next_sofar
-                        + compute_optimistic_flow_with_friend(
-                            &rooms,
-                            &next_left,
-                            at.me_time_left,
-                            time_left_after_open,
-                        );
+    fn part2(Cave { rooms, dist }: &Self::Parsed) -> Answer {
+        let matter = rooms
+            .iter()
+            .filter_map(|(s, r)| if r.flow > 0 { Some(s) } else { None })
+            .collect::<FxHashSet<_>>();
+        // println!("Matter: {:?}", matter);
 
-                    heap.push(ByFirst((
-                        theoretical_flow,
-                        Remember2 {
-                            me_at: at.me_at.clone(),
-                            me_time_left: at.me_time_left,
-                            elph_at: (*next).clone(),
-                            elph_time_left: time_left_after_open,
-                            sofar: next_sofar,
-                            rooms_left: next_left,
-                            me_followed: at.me_followed.clone(),
-                            elph_followed: followed,
-                        },
-                    )));
+        #[derive(Debug)]
+        struct Remember2<'a> {
+            me_at: String,
+            me_time_left: i32,
+
+            elph_at: String,
+            elph_time_left: i32,
+
+            sofar: i32, // Flow released so far.
+            rooms_left: FxHashSet<&'a String>,
+
+            me_followed: Vec<String>, // TODO: delete me
+            elph_followed: Vec<String>,
+        }
+
+        let mut heap: BinaryHeap<ByFirst<(i32, Remember2)>> = BinaryHeap::new();
+        heap.push(ByFirst((
+            i32::MAX,
+            Remember2 {
+                me_at: "AA".to_string(),
+                me_time_left: 26,
+                elph_at: "AA".to_string(),
+                elph_time_left: 26,
+                sofar: 0,
+                rooms_left: matter.clone(),
+                me_followed: Vec::new(),
+                elph_followed: Vec::new(),
+            },
+        )));
+
+        let mut best_sofar = 0;
+        while let Some(ByFirst((_theoretical, at))) = heap.pop() {
+            if at.sofar > best_sofar {
+                best_sofar = at.sofar;
+            }
+
+            if at.rooms_left.is_empty() || (at.me_time_left <= 2 && at.elph_time_left <= 2) {
+                break;
+            }
+
+            // Whoever's turn it is can also stop, and leave the rest to the other.
+            let (me_time_left, elph_time_left) = if at.me_time_left >= at.elph_time_left {
+                (0, at.elph_time_left)
+            } else {
+                (at.me_time_left, 0)
+            };
+            heap.push(ByFirst((
+                at.sofar + compute_optimistic_flow_with_friend(rooms, &at.rooms_left, me_time_left, elph_time_left),
+                Remember2 {
+                    me_at: at.me_at.clone(),
+                    me_time_left,
+                    elph_at: at.elph_at.clone(),
+                    elph_time_left,
+                    sofar: at.sofar,
+                    rooms_left: at.rooms_left.clone(),
+                    me_followed: at.me_followed.clone(),
+                    elph_followed: at.elph_followed.clone(),
+                },
+            )));
+
+            for next in &at.rooms_left {
+                let mut next_left = at.rooms_left.clone();
+                next_left.remove(next);
+
+                if at.me_time_left >= at.elph_time_left {
+                    // I move
+                    let mut followed = at.me_followed.clone();
+                    followed.push((*next).clone());
+
+                    let time_left_after_move =
+                        at.me_time_left - dist.get(&(at.me_at.clone(), (*next).clone())).unwrap();
+                    if time_left_after_move > 1 {
+                        let time_left_after_open = time_left_after_move - 1;
+                        let next_sofar =
+                            at.sofar + time_left_after_open * rooms.get(*next).unwrap().flow;
+
+                        let theoretical_flow = next_sofar
+                            + compute_optimistic_flow_with_friend(
+                                rooms,
+                                &next_left,
+                                time_left_after_open,
+                                at.elph_time_left,
+                            );
+
+                        heap.push(ByFirst((
+                            theoretical_flow,
+                            Remember2 {
+                                me_at: (*next).clone(),
+                                me_time_left: time_left_after_open,
+                                elph_at: at.elph_at.clone(),
+                                elph_time_left: at.elph_time_left,
+                                sofar: next_sofar,
+                                rooms_left: next_left,
+
+                                me_followed: followed,
+                                elph_followed: at.elph_followed.clone(),
+                            },
+                        )));
+                    }
+                } else {
+                    // Elephant moves
+                    let mut followed = at.elph_followed.clone();
+                    followed.push((*next).clone());
+
+                    let time_left_after_move =
+                        at.elph_time_left - dist.get(&(at.elph_at.clone(), (*next).clone())).unwrap();
+                    if time_left_after_move > 1 {
+                        let time_left_after_open = time_left_after_move - 1;
+                        let next_sofar =
+                            at.sofar + time_left_after_open * rooms.get(*next).unwrap().flow;
+
+                        let theoretical_flow = next_sofar
+                            + compute_optimistic_flow_with_friend(
+                                rooms,
+                                &next_left,
+                                at.me_time_left,
+                                time_left_after_open,
+                            );
+
+                        heap.push(ByFirst((
+                            theoretical_flow,
+                            Remember2 {
+                                me_at: at.me_at.clone(),
+                                me_time_left: at.me_time_left,
+                                elph_at: (*next).clone(),
+                                elph_time_left: time_left_after_open,
+                                sofar: next_sofar,
+                                rooms_left: next_left,
+                                me_followed: at.me_followed.clone(),
+                                elph_followed: followed,
+                            },
+                        )));
+                    }
                 }
             }
         }
+
+        best_sofar.into()
     }
 
-    let part2 = best_sofar;
-    if print {
-        println!("Day {}.  Part 2: {}", DAY, part2);
+    fn reference1(cave: &Self::Parsed) -> Option<Answer> {
+        Some((*best_by_valves(cave, 30).values().max().unwrap()).into())
+    }
+
+    // The best pair of plans that open different valves.
+    fn reference2(cave: &Self::Parsed) -> Option<Answer> {
+        let best = best_by_valves(cave, 26);
+        let mut part2 = 0;
+        for (mine, me) in &best {
+            for (theirs, elephant) in &best {
+                if !mine.iter().zip(theirs).any(|(mine, theirs)| *mine && *theirs) {
+                    part2 = part2.max(me + elephant);
+                }
+            }
+        }
+        Some(part2.into())
     }
-    assert_eq!(part2, if test_mode { 1707 } else { 2675 });
 }
 
-const TEST_EXAMPLE: &'static str = "Valve AA has flow rate=0; tunnels lead to valves DD, II, BB
+const TEST_EXAMPLE: &str = "Valve AA has flow rate=0; tunnels lead to valves DD, II, BB
 Valve BB has flow rate=13; tunnels lead to valves CC, AA
 Valve CC has flow rate=2; tunnels lead to valves DD, BB
 Valve DD has flow rate=20; tunnels lead to valves CC, AA, EE
@@ -352,3 +478,77 @@ Valve GG has flow rate=0; tunnels lead to valves FF, HH
 Valve HH has flow rate=22; tunnel leads to valve GG
 Valve II has flow rate=0; tunnels lead to valves AA, JJ
 Valve JJ has flow rate=21; tunnel leads to valve II";
+
+// Where the best plan opens its last valve with one minute to go.
+#[cfg(test)]
+const LAST_MINUTE_EXAMPLE: &str = "Valve OA has flow rate=1; tunnels lead to valves CM, CB, OU, UF, HS, TY
+Valve CM has flow rate=5; tunnels lead to valves OA, NK, JN
+Valve CB has flow rate=21; tunnels lead to valves OA, NK, GV, PU
+Valve NK has flow rate=1; tunnels lead to valves CM, CB, DE
+Valve DE has flow rate=11; tunnels lead to valves NK, AR, EV
+Valve OU has flow rate=6; tunnels lead to valves OA, XG, QK, KU, FA, LJ, EO
+Valve UF has flow rate=12; tunnels lead to valves OA, PM, WX
+Valve GV has flow rate=14; tunnels lead to valves CB, AR
+Valve PU has flow rate=8; tunnels lead to valves CB, JY, DN
+Valve XG has flow rate=8; tunnels lead to valves OU, LU, EV
+Valve HS has flow rate=0; tunnels lead to valves OA, AR
+Valve QK has flow rate=0; tunnel leads to valve OU
+Valve KU has flow rate=0; tunnel leads to valve OU
+Valve PM has flow rate=0; tunnels lead to valves UF, PC, AA
+Valve PC has flow rate=0; tunnels lead to valves PM, LD
+Valve JN has flow rate=0; tunnels lead to valves CM, KC
+Valve LU has flow rate=0; tunnels lead to valves XG, ES
+Valve KC has flow rate=0; tunnel leads to valve JN
+Valve LD has flow rate=0; tunnels lead to valves PC, EV
+Valve WX has flow rate=0; tunnel leads to valve UF
+Valve ES has flow rate=0; tunnel leads to valve LU
+Valve FA has flow rate=0; tunnels lead to valves OU, LJ
+Valve LJ has flow rate=0; tunnels lead to valves OU, FA
+Valve JY has flow rate=0; tunnel leads to valve PU
+Valve AR has flow rate=0; tunnels lead to valves DE, GV, HS
+Valve TY has flow rate=0; tunnels lead to valves OA, EV
+Valve DN has flow rate=0; tunnels lead to valves PU, EO
+Valve AA has flow rate=0; tunnel leads to valve PM
+Valve EV has flow rate=0; tunnels lead to valves DE, XG, LD, TY
+Valve EO has flow rate=0; tunnels lead to valves OU, DN";
+
+// Where the one with more time left has to stop early, so the other can get to
+// the valves it would have taken.
+#[cfg(test)]
+const STOP_EARLY_EXAMPLE: &str = "Valve GJ has flow rate=4; tunnels lead to valves DO, VP, KW, AA, OV
+Valve DO has flow rate=21; tunnels lead to valves GJ, QQ, KW
+Valve VP has flow rate=21; tunnel leads to valve GJ
+Valve QQ has flow rate=11; tunnels lead to valves DO, NQ
+Valve KW has flow rate=18; tunnels lead to valves GJ, DO, AA
+Valve NQ has flow rate=2; tunnels lead to valves QQ, NI
+Valve AA has flow rate=0; tunnels lead to valves GJ, KW, OV
+Valve OV has flow rate=0; tunnels lead to valves GJ, AA, VN
+Valve VN has flow rate=0; tunnels lead to valves OV, OL
+Valve NI has flow rate=0; tunnel leads to valve NQ
+Valve OL has flow rate=0; tunnel leads to valve VN";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::testing::{part1, part2};
+
+    #[test]
+    fn example_part1() {
+        assert_eq!(part1::<Day16>(TEST_EXAMPLE), 1651.into());
+    }
+
+    #[test]
+    fn example_part2() {
+        assert_eq!(part2::<Day16>(TEST_EXAMPLE), 1707.into());
+    }
+
+    #[test]
+    fn last_minute_example_part1() {
+        assert_eq!(part1::<Day16>(LAST_MINUTE_EXAMPLE), 1673.into());
+    }
+
+    #[test]
+    fn stop_early_example_part2() {
+        assert_eq!(part2::<Day16>(STOP_EARLY_EXAMPLE), 1717.into());
+    }
+}
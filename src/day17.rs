@@ -4,13 +4,13 @@ use std::{
     hash::{Hash, Hasher},
 };
 
-use rustc_hash::FxHashMap;
+use rustc_hash::{FxHashMap, FxHashSet};
 
-use crate::utils::Grid;
-
-const DAY: i32 = 17;
+use crate::solution::{Answer, Solution};
+use crate::utils::{lines, Grid, ParseError};
 
 #[rustfmt::skip]
+#[allow(dead_code)]
 const ROCK_STRINGS: [&str; 5] = [
 "####",
 
@@ -30,8 +30,11 @@ const ROCK_STRINGS: [&str; 5] = [
 "##
 ##"];
 
+const JETS: [(&str, i8); 2] = [("<", -1), (">", 1)];
+
 const WALLS: u16 = 0b1_0000_0001;
 
+#[allow(dead_code)]
 fn parse_rock(string: &str) -> Grid<u8> {
     let lines = string.lines().map(|s| s.as_bytes()).collect::<Vec<_>>();
     let width = lines[0].len();
@@ -69,16 +72,16 @@ impl State {
     }
 }
 
-fn collides(cave: &Vec<u16>, rock: &Vec<u16>, r: i32, c: i32) -> bool {
+fn collides(cave: &[u16], rock: &[u16], r: i32, c: i32) -> bool {
     if r < 0 {
         return true;
     }
 
     let offset = 1 + c;
 
-    for rock_r in 0..rock.len() {
+    for (rock_r, rock_row) in rock.iter().enumerate() {
         let cave_r = r as usize + rock_r;
-        if (rock[rock_r] << offset) & cave[cave_r] > 0 {
+        if (rock_row << offset) & cave[cave_r] > 0 {
             return true;
         }
     }
@@ -86,11 +89,11 @@ fn collides(cave: &Vec<u16>, rock: &Vec<u16>, r: i32, c: i32) -> bool {
     false
 }
 
-fn blit(cave: &mut Vec<u16>, rock: &Vec<u16>, r: usize, c: usize) {
+fn blit(cave: &mut [u16], rock: &[u16], r: usize, c: usize) {
     let shift = 1 + c;
-    for rock_r in 0..rock.len() {
+    for (rock_r, rock_row) in rock.iter().enumerate() {
         let cave_r = r + rock_r;
-        cave[cave_r] |= rock[rock_r] << shift;
+        cave[cave_r] |= rock_row << shift;
     }
 }
 
@@ -104,7 +107,7 @@ fn drop_rocks<'a, RockIt, BlowIt>(
     BlowIt: Iterator<Item = (usize, i8)>,
 {
     state.cave.reserve(num_rocks * 3);
-    for i in 0..num_rocks {
+    for _ in 0..num_rocks {
         let mut drop_c: i32 = 2;
         let mut drop_r: i32 = state.top as i32 + 3;
 
@@ -118,14 +121,14 @@ fn drop_rocks<'a, RockIt, BlowIt>(
             // Sideways
             let (blow_id, blow) = blow_iter.next().unwrap();
             state.last_blow = blow_id;
-            if !collides(&state.cave, &rock, drop_r, drop_c + blow as i32) {
+            if !collides(&state.cave, rock, drop_r, drop_c + blow as i32) {
                 drop_c += blow as i32;
             }
 
             // Down
-            if drop_r >= 0 && collides(&state.cave, &rock, drop_r - 1, drop_c) {
+            if drop_r >= 0 && collides(&state.cave, rock, drop_r - 1, drop_c) {
                 // Places the rock
-                blit(&mut state.cave, &rock, drop_r as usize, drop_c as usize);
+                blit(&mut state.cave, rock, drop_r as usize, drop_c as usize);
                 state.top = max(state.top, drop_r as usize + rock.len());
                 break;
             } else {
@@ -137,11 +140,7 @@ fn drop_rocks<'a, RockIt, BlowIt>(
 }
 
 fn hash_crown_occupancy(state: &State, rows: usize) -> usize {
-    let start_row = if rows < state.top {
-        state.top - rows
-    } else {
-        0
-    };
+    let start_row = state.top.saturating_sub(rows);
     let mut hash = DefaultHasher::new();
     for d in &state.cave[start_row..state.top] {
         d.hash(&mut hash);
@@ -151,7 +150,8 @@ fn hash_crown_occupancy(state: &State, rows: usize) -> usize {
     hash.finish() as usize
 }
 
-fn fmt_cave(cave: &Vec<u16>) -> String {
+#[allow(dead_code)]
+fn fmt_cave(cave: &[u16]) -> String {
     let mut out = String::with_capacity(cave.len() * 10);
 
     for row in cave.iter().rev() {
@@ -162,7 +162,7 @@ fn fmt_cave(cave: &Vec<u16>) -> String {
                 1 => '#',
                 _ => unreachable!(),
             });
-            val = val >> 1;
+            val >>= 1;
         }
         out.push('\n');
     }
@@ -170,129 +170,205 @@ fn fmt_cave(cave: &Vec<u16>) -> String {
     out
 }
 
-pub fn day17(test_mode: bool, print: bool) {
-    let file_str = std::fs::read_to_string(format!("inputs/input{:02}.txt", DAY)).unwrap();
-    let input_str = if test_mode {
-        TEST_EXAMPLE
-    } else {
-        &file_str.trim_end()
-    };
-
-    let looping_after = input_str.len() * ROCK_STRINGS.len();
-
-    // let rocks = ROCK_STRINGS.map(|string| parse_rock(string));
-
-    // LSB is leftwards, and rock[0] is the bottom, so these
-    // look backwards and upside down.
-    //
-    // Got a little to lazy to parse these.
-    let brocks = [
+// LSB is leftwards, and rock[0] is the bottom, so these
+// look backwards and upside down.
+//
+// Got a little to lazy to parse these.
+fn brocks() -> [Vec<u16>; 5] {
+    [
         vec![0b1111],
         vec![0b010, 0b111, 0b010],
         vec![0b111, 0b100, 0b100], // vec![0b001, 0b001, 0b111],
         vec![0b1, 0b1, 0b1, 0b1],
         vec![0b11, 0b11],
-    ];
-
-    let mut blow_iter = input_str
-        .as_bytes()
-        .iter()
-        .map(|ch| if *ch == b'<' { -1i8 } else { 1 })
-        .enumerate()
-        .cycle();
-    let mut rock_iter = brocks.iter().enumerate().cycle();
-
-    let mut state = State::new();
-    drop_rocks(
-        &mut state,
-        2022,
-        &mut rock_iter.clone(),
-        &mut blow_iter.clone(),
-    );
-
-    // println!("{}", fmt_cave(&state.cave));
-
-    // Up is +row
-
-    let part1 = state.top;
-    if print {
-        println!("Day {}.  Part 1: {}", DAY, part1);
+    ]
+}
+
+// The rocks as (x, y) cells from their bottom left, for the reference
+// versions of the parts.
+const ROCK_CELLS: [&[(i64, i64)]; 5] = [
+    &[(0, 0), (1, 0), (2, 0), (3, 0)],
+    &[(1, 0), (0, 1), (1, 1), (2, 1), (1, 2)],
+    &[(0, 0), (1, 0), (2, 0), (2, 1), (2, 2)],
+    &[(0, 0), (0, 1), (0, 2), (0, 3)],
+    &[(0, 0), (1, 0), (0, 1), (1, 1)],
+];
+
+// The height of the tower after each of the first `count` rocks, simulated a
+// cell at a time. The floor is y = 0.
+fn tower_heights(jets: &[i8], count: usize) -> Vec<i64> {
+    let mut settled = FxHashSet::default();
+    let mut heights = vec![0];
+    let mut jet = 0;
+    let free = |settled: &FxHashSet<(i64, i64)>, x: i64, y: i64| (0..7).contains(&x) && y > 0 && !settled.contains(&(x, y));
+    for rock in 0..count {
+        let cells = ROCK_CELLS[rock % 5];
+        let (mut x, mut y) = (2, heights[rock] + 4);
+        loop {
+            let pushed = x + jets[jet % jets.len()] as i64;
+            jet += 1;
+            if cells.iter().all(|(dx, dy)| free(&settled, pushed + dx, y + dy)) {
+                x = pushed;
+            }
+            if !cells.iter().all(|(dx, dy)| free(&settled, x + dx, y - 1 + dy)) {
+                break;
+            }
+            y -= 1;
+        }
+        settled.extend(cells.iter().map(|(dx, dy)| (x + dx, y + dy)));
+        let top = cells.iter().map(|(_, dy)| y + dy).max().unwrap();
+        heights.push(heights[rock].max(top));
     }
-    assert_eq!(part1, if test_mode { 3068 } else { 3206 });
+    heights
+}
 
-    // In part 2, we look for a repeated section, and then simulate the looping.
+pub struct Day17;
 
-    // Really we should search for a full blockage, but probably this value is big enough.
-    const ASSUME_TRIMMABLE: usize = 50;
+impl Solution for Day17 {
+    const DAY: u32 = 17;
+    const EXAMPLE: &'static str = TEST_EXAMPLE;
 
-    struct Breadcrumb {
-        iters: usize,
-        top: usize,
-    }
-    let mut breadcrumbs =
-        FxHashMap::<usize, Breadcrumb>::with_capacity_and_hasher(32, Default::default());
-
-    // It's important to use the same rock_iter and blow_iter throughout.
-
-    // Looks for where the repititions begin.
-    let mut iter_count = 0;
-    let mut state = State::new();
-    let mut loop_iter_increase = 0;
-    let mut loop_top_increase = 0;
-
-    loop {
-        drop_rocks(&mut state, 1, &mut rock_iter, &mut blow_iter);
-        iter_count += 1;
-
-        let crown_hash = hash_crown_occupancy(&state, ASSUME_TRIMMABLE);
-        if let Some(seen) = breadcrumbs.get(&crown_hash) {
-            loop_iter_increase = iter_count - seen.iters;
-            loop_top_increase = state.top - seen.top;
-            break;
+    type Parsed = Vec<i8>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        let mut lines = lines(input);
+        let mut line = lines.next_line("the jet pattern")?;
+        let mut blows = Vec::with_capacity(line.rest().len());
+        loop {
+            blows.push(line.one_of(&JETS)?);
+            if line.at_end() {
+                break;
+            }
         }
+        match lines.next() {
+            Some(extra) => Err(extra.error("just the one line")),
+            None => Ok(blows),
+        }
+    }
 
-        breadcrumbs.insert(
-            crown_hash,
-            Breadcrumb {
-                iters: iter_count,
-                top: state.top,
+    fn lint(input: &str) -> Vec<ParseError> {
+        let mut errors = Vec::new();
+        let mut lines = lines(input);
+        match lines.next_line("the jet pattern") {
+            Err(err) => errors.push(err),
+            Ok(mut line) => loop {
+                if let Err(err) = line.one_of(&JETS) {
+                    errors.push(err);
+                    // Carries on after the bad character.
+                    let _ = line.char_where("", |_| true);
+                }
+                if line.at_end() {
+                    break;
+                }
             },
-        );
+        }
+        errors.extend(lines.map(|extra| extra.error("just the one line")));
+        errors
     }
 
-    // println!(
-    //     "Found loop at {} (top: {}), with d-iters = {}, d-top = {}",
-    //     iter_count, state.top, loop_iter_increase, loop_top_increase
-    // );
-
-    const ITERATIONS: usize = 1_000_000_000_000;
-
-    // Now we fake some repetitions
-    let num_cycles_to_fake = (ITERATIONS - iter_count) / loop_iter_increase;
-    let fake_iter_increase = num_cycles_to_fake * loop_iter_increase;
-    let fake_top_increase = num_cycles_to_fake * loop_top_increase;
-
-    // And then do any extra iterations
-    let left_iterations = ITERATIONS - (iter_count + fake_iter_increase);
-    drop_rocks(
-        &mut state,
-        left_iterations,
-        &mut rock_iter.clone(),
-        &mut blow_iter.clone(),
-    );
-
-    let part2 = state.top + fake_top_increase;
-    if print {
-        println!("Day {}.  Part 2: {}", DAY, part2);
+    fn part1(blows: &Self::Parsed) -> Answer {
+        // let rocks = ROCK_STRINGS.map(|string| parse_rock(string));
+        let brocks = brocks();
+
+        let mut blow_iter = blows.iter().copied().enumerate().cycle();
+        let mut rock_iter = brocks.iter().enumerate().cycle();
+
+        let mut state = State::new();
+        drop_rocks(&mut state, 2022, &mut rock_iter, &mut blow_iter);
+
+        // println!("{}", fmt_cave(&state.cave));
+
+        // Up is +row
+
+        state.top.into()
     }
-    assert_eq!(
-        part2,
-        if test_mode {
-            1514285714288
-        } else {
-            1602881844347
+
+    fn part2(blows: &Self::Parsed) -> Answer {
+        // In part 2, we look for a repeated section, and then simulate the looping.
+        let brocks = brocks();
+
+        let mut blow_iter = blows.iter().copied().enumerate().cycle();
+        let mut rock_iter = brocks.iter().enumerate().cycle();
+
+        // Really we should search for a full blockage, but probably this value is big enough.
+        const ASSUME_TRIMMABLE: usize = 50;
+
+        struct Breadcrumb {
+            iters: usize,
+            top: usize,
         }
-    );
+        let mut breadcrumbs =
+            FxHashMap::<usize, Breadcrumb>::with_capacity_and_hasher(32, Default::default());
+
+        // It's important to use the same rock_iter and blow_iter throughout.
+
+        // Looks for where the repititions begin.
+        let mut iter_count = 0;
+        let mut state = State::new();
+
+        let (loop_iter_increase, loop_top_increase) = loop {
+            drop_rocks(&mut state, 1, &mut rock_iter, &mut blow_iter);
+            iter_count += 1;
+
+            let crown_hash = hash_crown_occupancy(&state, ASSUME_TRIMMABLE);
+            if let Some(seen) = breadcrumbs.get(&crown_hash) {
+                break (iter_count - seen.iters, state.top - seen.top);
+            }
+
+            breadcrumbs.insert(
+                crown_hash,
+                Breadcrumb {
+                    iters: iter_count,
+                    top: state.top,
+                },
+            );
+        };
+
+        // println!(
+        //     "Found loop at {} (top: {}), with d-iters = {}, d-top = {}",
+        //     iter_count, state.top, loop_iter_increase, loop_top_increase
+        // );
+
+        const ITERATIONS: usize = 1_000_000_000_000;
+
+        // Now we fake some repetitions
+        let num_cycles_to_fake = (ITERATIONS - iter_count) / loop_iter_increase;
+        let fake_iter_increase = num_cycles_to_fake * loop_iter_increase;
+        let fake_top_increase = num_cycles_to_fake * loop_top_increase;
+
+        // And then do any extra iterations
+        let left_iterations = ITERATIONS - (iter_count + fake_iter_increase);
+        drop_rocks(
+            &mut state,
+            left_iterations,
+            &mut rock_iter.clone(),
+            &mut blow_iter.clone(),
+        );
+
+        (state.top + fake_top_increase).into()
+    }
+
+    // Part 2 has no reference: a trillion rocks can't be dropped one at a
+    // time, and anything quicker would be finding the period again.
+    fn reference1(jets: &Self::Parsed) -> Option<Answer> {
+        Some(tower_heights(jets, 2022)[2022].into())
+    }
 }
 
-const TEST_EXAMPLE: &'static str = ">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>";
+const TEST_EXAMPLE: &str = ">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::testing::{part1, part2};
+
+    #[test]
+    fn example_part1() {
+        assert_eq!(part1::<Day17>(TEST_EXAMPLE), 3068.into());
+    }
+
+    #[test]
+    fn example_part2() {
+        assert_eq!(part2::<Day17>(TEST_EXAMPLE), 1514285714288i64.into());
+    }
+}
@@ -5,16 +5,15 @@ use std::{
 
 use rustc_hash::FxHashSet;
 
-use crate::utils::Pt3;
-
-const DAY: i32 = 18;
+use crate::solution::{Answer, Solution};
+use crate::utils::{lines, lint_lines, Line, ParseError, Pt3};
 
 struct Neighbor6Iterator<'a> {
     point: &'a Pt3,
     at: u8,
 }
 
-fn neighbors6(point: &Pt3) -> Neighbor6Iterator {
+fn neighbors6(point: &Pt3) -> Neighbor6Iterator<'_> {
     Neighbor6Iterator { point, at: 0 }
 }
 
@@ -39,97 +38,100 @@ impl<'a> Iterator for Neighbor6Iterator<'a> {
     }
 }
 
-pub fn day18(test_mode: bool, print: bool) {
-    let file_str = std::fs::read_to_string(format!("inputs/input{:02}.txt", DAY)).unwrap();
-    let input_str = if test_mode {
-        TEST_EXAMPLE
-    } else {
-        &file_str.trim_end()
-    };
-
-    fn parse_point(s: &str) -> Pt3 {
-        let mut pieces = s.split(",");
-        let x = pieces.next().unwrap().parse().unwrap();
-        let y = pieces.next().unwrap().parse().unwrap();
-        let z = pieces.next().unwrap().parse().unwrap();
-        Pt3::new(x, y, z)
+fn parse_point(mut line: Line) -> Result<Pt3, ParseError> {
+    let x = line.integer()?;
+    line.literal(",")?;
+    let y = line.integer()?;
+    line.literal(",")?;
+    let z = line.integer()?;
+    line.end()?;
+    Ok(Pt3::new(x, y, z))
+}
+
+pub struct Day18;
+
+impl Solution for Day18 {
+    const DAY: u32 = 18;
+    const EXAMPLE: &'static str = TEST_EXAMPLE;
+
+    type Parsed = FxHashSet<Pt3>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        lines(input).map(parse_point).collect()
     }
 
-    let points = input_str.lines().map(parse_point).collect::<FxHashSet<_>>();
+    fn lint(input: &str) -> Vec<ParseError> {
+        lint_lines(input, parse_point)
+    }
 
-    let mut exposed = 0usize;
-    for point in &points {
-        for n in neighbors6(point) {
-            if !points.contains(&n) {
-                exposed += 1;
+    fn part1(points: &Self::Parsed) -> Answer {
+        let mut exposed = 0usize;
+        for point in points {
+            for n in neighbors6(point) {
+                if !points.contains(&n) {
+                    exposed += 1;
+                }
             }
         }
+        exposed.into()
     }
 
-    let part1 = exposed;
-    if print {
-        println!("Day {}.  Part 1: {}", DAY, part1);
-    }
-    assert_eq!(part1, if test_mode { 64 } else { 3500 });
-
-    let mut lo = Pt3::new(i32::MAX, i32::MAX, i32::MAX);
-    let mut hi = Pt3::new(i32::MIN, i32::MIN, i32::MIN);
-    for p in &points {
-        lo.x = min(lo.x, p.x);
-        lo.y = min(lo.y, p.y);
-        lo.z = min(lo.z, p.z);
-        hi.x = max(hi.x, p.x);
-        hi.y = max(hi.y, p.y);
-        hi.z = max(hi.z, p.z);
-    }
-    lo = lo.plus_coords(-1, -1, -1);
-    hi = hi.plus_coords(1, 1, 1);
-
-    let start = Pt3::new(lo.x, (lo.y + hi.y) / 2, (lo.z + hi.z) / 2);
-    let mut queue = VecDeque::<Pt3>::with_capacity(((hi.y - lo.y) * (hi.z - lo.z)) as usize);
-    let mut air = FxHashSet::<Pt3>::with_capacity_and_hasher(
-        ((hi.x - lo.x) * (hi.y - lo.y) * (hi.z - lo.z)) as usize,
-        Default::default(),
-    );
-    queue.push_back(start.clone());
-    air.insert(start);
-    while !queue.is_empty() {
-        let p = queue.pop_front().unwrap();
-        for n in neighbors6(&p) {
-            if lo.x <= n.x
-                && n.x <= hi.x
-                && lo.y <= n.y
-                && n.y <= hi.y
-                && lo.z <= n.z
-                && n.z <= hi.z
-                && !points.contains(&n)
-                && !air.contains(&n)
-            {
-                air.insert(n.clone());
-                queue.push_back(n);
+    fn part2(points: &Self::Parsed) -> Answer {
+        let mut lo = Pt3::new(i32::MAX, i32::MAX, i32::MAX);
+        let mut hi = Pt3::new(i32::MIN, i32::MIN, i32::MIN);
+        for p in points {
+            lo.x = min(lo.x, p.x);
+            lo.y = min(lo.y, p.y);
+            lo.z = min(lo.z, p.z);
+            hi.x = max(hi.x, p.x);
+            hi.y = max(hi.y, p.y);
+            hi.z = max(hi.z, p.z);
+        }
+        lo = lo.plus_coords(-1, -1, -1);
+        hi = hi.plus_coords(1, 1, 1);
+
+        let start = Pt3::new(lo.x, (lo.y + hi.y) / 2, (lo.z + hi.z) / 2);
+        let mut queue = VecDeque::<Pt3>::with_capacity(((hi.y - lo.y) * (hi.z - lo.z)) as usize);
+        let mut air = FxHashSet::<Pt3>::with_capacity_and_hasher(
+            ((hi.x - lo.x) * (hi.y - lo.y) * (hi.z - lo.z)) as usize,
+            Default::default(),
+        );
+        queue.push_back(start.clone());
+        air.insert(start);
+        while !queue.is_empty() {
+            let p = queue.pop_front().unwrap();
+            for n in neighbors6(&p) {
+                if lo.x <= n.x
+                    && n.x <= hi.x
+                    && lo.y <= n.y
+                    && n.y <= hi.y
+                    && lo.z <= n.z
+                    && n.z <= hi.z
+                    && !points.contains(&n)
+                    && !air.contains(&n)
+                {
+                    air.insert(n.clone());
+                    queue.push_back(n);
+                }
             }
         }
-    }
 
-    // Got all the air now.
+        // Got all the air now.
 
-    let mut exposed = 0usize;
-    for point in &points {
-        for n in neighbors6(point) {
-            if air.contains(&n) {
-                exposed += 1;
+        let mut exposed = 0usize;
+        for point in points {
+            for n in neighbors6(point) {
+                if air.contains(&n) {
+                    exposed += 1;
+                }
             }
         }
-    }
 
-    let part2 = exposed;
-    if print {
-        println!("Day {}.  Part 2: {}", DAY, part2);
+        exposed.into()
     }
-    assert_eq!(part2, if test_mode { 58 } else { 2048 });
 }
 
-const TEST_EXAMPLE: &'static str = "2,2,2
+const TEST_EXAMPLE: &str = "2,2,2
 1,2,2
 3,2,2
 2,1,2
@@ -142,3 +144,24 @@ const TEST_EXAMPLE: &'static str = "2,2,2
 3,2,5
 2,1,5
 2,3,5";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::testing::{part1, part2};
+
+    #[test]
+    fn example_part1() {
+        assert_eq!(part1::<Day18>(TEST_EXAMPLE), 64.into());
+    }
+
+    #[test]
+    fn example_part2() {
+        assert_eq!(part2::<Day18>(TEST_EXAMPLE), 58.into());
+    }
+
+    #[test]
+    fn two_cubes_part1() {
+        assert_eq!(part1::<Day18>("1,1,1\n2,1,1"), 10.into());
+    }
+}
@@ -0,0 +1,200 @@
+use std::cell::Cell;
+use std::fmt;
+use std::ops::Range;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Once;
+
+use crate::gen::{self, Size};
+use crate::solution::{Answer, DynSolution, Parts};
+
+// A part's answer, or what it panicked with.
+pub type Outcome = Result<Answer, String>;
+
+// A generated input that a day's fast part and its reference give different
+// answers for, shrunk down as far as they still do.
+#[derive(Debug)]
+pub struct Disagreement {
+    pub day: u32,
+    pub part: u32,
+    pub seed: u64,
+    pub input: String,
+    pub fast: Outcome,
+    pub reference: Outcome,
+}
+
+fn fmt_outcome(outcome: &Outcome) -> String {
+    match outcome {
+        Ok(answer) => answer.to_string(),
+        Err(message) => format!("panicked: {}", message),
+    }
+}
+
+impl fmt::Display for Disagreement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let lines = self.input.lines().count();
+        writeln!(f, "Day {} part {} disagrees on seed {} (shrunk to {} lines):", self.day, self.part, self.seed, lines)?;
+        writeln!(f, "{}", self.input)?;
+        writeln!(f, "fast:      {}", fmt_outcome(&self.fast))?;
+        write!(f, "reference: {}", fmt_outcome(&self.reference))
+    }
+}
+
+thread_local! {
+    static QUIET: Cell<bool> = const { Cell::new(false) };
+}
+
+// Runs `f`, turning a panic into its message. The panic isn't printed, since
+// shrinking expects plenty of them.
+fn quietly<T>(f: impl FnOnce() -> T) -> Result<T, String> {
+    static HOOK: Once = Once::new();
+    HOOK.call_once(|| {
+        let default = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !QUIET.with(Cell::get) {
+                default(info);
+            }
+        }));
+    });
+
+    QUIET.with(|quiet| quiet.set(true));
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    QUIET.with(|quiet| quiet.set(false));
+    result.map_err(|payload| {
+        payload.downcast_ref::<&str>().map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "something that isn't a string".to_string())
+    })
+}
+
+// Runs one part both ways, the reference first. None if there's nothing to
+// compare: the input doesn't lint clean, or there's no reference for the part.
+fn compare(solution: &dyn DynSolution, input: &str, test_mode: bool, part: u32) -> Option<(Outcome, Outcome)> {
+    if !solution.lint(input).is_empty() {
+        return None;
+    }
+    let answer = |answers: (Option<Answer>, Option<Answer>)| if part == 1 { answers.0 } else { answers.1 };
+    let reference = match quietly(|| solution.solve_reference(input, test_mode, Parts::Only(part))) {
+        Ok(Ok(answers)) => Ok(answer(answers)?),
+        Ok(Err(_)) => return None,
+        Err(message) => Err(message),
+    };
+    let fast = quietly(|| solution.solve(input, test_mode, Parts::Only(part)))
+        .map(|answers| answer(answers.unwrap()).unwrap());
+    Some((fast, reference))
+}
+
+// Drops whatever `fails` still holds for without: chunks of lines, halving
+// the chunks down to single lines, then characters within each line.
+fn shrink(input: &str, fails: impl Fn(&str) -> bool) -> String {
+    let mut lines: Vec<String> = input.lines().map(str::to_string).collect();
+    let mut chunk = lines.len().div_ceil(2).max(1);
+    loop {
+        let mut start = 0;
+        let mut removed = false;
+        while start < lines.len() {
+            let end = (start + chunk).min(lines.len());
+            let candidate = [&lines[..start], &lines[end..]].concat();
+            if !candidate.is_empty() && fails(&candidate.join("\n")) {
+                lines = candidate;
+                removed = true;
+            } else {
+                start = end;
+            }
+        }
+        if chunk == 1 && !removed {
+            break;
+        }
+        if !removed {
+            chunk = chunk.div_ceil(2);
+        }
+    }
+
+    for row in 0..lines.len() {
+        let mut at = 0;
+        while at < lines[row].len() {
+            let mut candidate = lines.clone();
+            candidate[row].remove(at);
+            if fails(&candidate.join("\n")) {
+                lines = candidate;
+            } else {
+                at += lines[row][at..].chars().next().unwrap().len_utf8();
+            }
+        }
+    }
+    lines.join("\n")
+}
+
+// Checks a day's fast parts against its reference on generated inputs of one
+// size, for each seed. Returns how many inputs were compared and agreed (so 0
+// for days without a reference), or the first disagreement, shrunk. A
+// reference that panics on a generated input means the generator broke a
+// promise, so that's reported as it is.
+pub fn check(solution: &dyn DynSolution, size: Size, seeds: Range<u64>) -> Result<usize, Box<Disagreement>> {
+    let test_mode = size == Size::Example;
+    let mut agreed = 0;
+    for seed in seeds {
+        let Some(input) = gen::generate(solution.day(), size, seed) else {
+            return Ok(agreed);
+        };
+        let input = input.trim_end();
+        let mut compared = false;
+        for part in 1..=2 {
+            let Some((fast, reference)) = compare(solution, input, test_mode, part) else {
+                continue;
+            };
+            compared = true;
+            if fast == reference {
+                continue;
+            }
+            let input = if reference.is_ok() {
+                shrink(input, |candidate| {
+                    matches!(compare(solution, candidate, test_mode, part), Some((fast, reference @ Ok(_))) if fast != reference)
+                })
+            } else {
+                input.to_string()
+            };
+            let (fast, reference) = compare(solution, &input, test_mode, part).unwrap();
+            return Err(Box::new(Disagreement { day: solution.day(), part, seed, input, fast, reference }));
+        }
+        agreed += compared as usize;
+    }
+    Ok(agreed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn references_solve_the_examples() {
+        for solution in crate::DAYS {
+            let example = solution.example().trim_end();
+            let (ref1, ref2) = solution.solve_reference(example, true, Parts::Both).unwrap();
+            let (part1, part2) = solution.solve(example, true, Parts::Both).unwrap();
+            if ref1.is_some() {
+                assert_eq!(ref1, part1, "day {} part 1", solution.day());
+            }
+            if ref2.is_some() {
+                assert_eq!(ref2, part2, "day {} part 2", solution.day());
+            }
+        }
+    }
+
+    #[test]
+    fn generated_examples_agree() {
+        for solution in crate::DAYS {
+            if let Err(disagreement) = check(*solution, Size::Example, 0..20) {
+                panic!("{}", disagreement);
+            }
+        }
+    }
+
+    #[test]
+    fn shrinks_to_the_lines_that_matter() {
+        let input = "1\n3\n5\n7\n9\n11";
+        let fails = |input: &str| input.lines().any(|line| line == "3") && input.lines().any(|line| line == "7");
+        assert_eq!(shrink(input, fails), "3\n7");
+        assert_eq!(shrink("abcdef", |input: &str| input.contains('d')), "d");
+        assert!(quietly(|| panic!("boom")).unwrap_err().contains("boom"));
+    }
+}
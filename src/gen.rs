@@ -0,0 +1,765 @@
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+// xorshift64*, seeded through splitmix64 so small seeds still start well mixed.
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        let mut z = seed.wrapping_add(0x9e3779b97f4a7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        Rng((z ^ (z >> 31)) | 1)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545f4914f6cdd1d)
+    }
+
+    // 0..n
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    // lo..=hi
+    pub fn range(&mut self, lo: i64, hi: i64) -> i64 {
+        lo + (self.next_u64() % (hi - lo + 1) as u64) as i64
+    }
+
+    pub fn chance(&mut self, percent: usize) -> bool {
+        self.below(100) < percent
+    }
+
+    pub fn pick<T: Copy>(&mut self, items: &[T]) -> T {
+        items[self.below(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+// How big an input to make.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Size {
+    // About as big as the puzzle's example, to be solved in test mode (-t).
+    Example,
+    // As big as a real input, times this.
+    Full(usize),
+}
+
+impl Size {
+    // `example` things for an example, or else `full` times the scale.
+    fn scaled(self, example: usize, full: usize) -> usize {
+        match self {
+            Size::Example => example,
+            Size::Full(scale) => full * scale,
+        }
+    }
+
+    fn either<T>(self, example: T, full: T) -> T {
+        match self {
+            Size::Example => example,
+            Size::Full(_) => full,
+        }
+    }
+}
+
+const LOWER: &[u8] = b"abcdefghijklmnopqrstuvwxyz";
+const LETTERS: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+// Calorie counts for 250 elves per scale.
+fn day01(rng: &mut Rng, size: Size) -> String {
+    let mut out = String::new();
+    for elf in 0..size.scaled(5, 250) {
+        if elf > 0 {
+            out.push('\n');
+        }
+        for _ in 0..rng.range(1, size.either(3, 15)) {
+            writeln!(out, "{}", rng.range(1000, 60000)).unwrap();
+        }
+    }
+    out
+}
+
+// 2500 rounds per scale.
+fn day02(rng: &mut Rng, size: Size) -> String {
+    let mut out = String::new();
+    for _ in 0..size.scaled(10, 2500) {
+        writeln!(out, "{} {}", rng.pick(&['A', 'B', 'C']), rng.pick(&['X', 'Y', 'Z'])).unwrap();
+    }
+    out
+}
+
+// 100 groups of three rucksacks per scale. In each group the letters are
+// dealt out so each rucksack's halves share exactly one item, and the three
+// rucksacks share exactly one badge.
+fn day03(rng: &mut Rng, size: Size) -> String {
+    let mut out = String::new();
+    for _ in 0..size.scaled(2, 100) {
+        let mut letters = LETTERS.to_vec();
+        rng.shuffle(&mut letters);
+        let badge = letters[0];
+        for elf in letters[1..].chunks(17) {
+            let (common, left, right) = (elf[0], &elf[1..9], &elf[9..]);
+            let half = rng.below(14) + 2;
+            let mut first: Vec<u8> = (0..half - 2).map(|_| rng.pick(left)).collect();
+            first.extend([common, badge]);
+            let mut second: Vec<u8> = (0..half - 1).map(|_| rng.pick(right)).collect();
+            second.push(common);
+            rng.shuffle(&mut first);
+            rng.shuffle(&mut second);
+            out.push_str(std::str::from_utf8(&first).unwrap());
+            out.push_str(std::str::from_utf8(&second).unwrap());
+            out.push('\n');
+        }
+    }
+    out
+}
+
+// 1000 pairs of section ranges per scale.
+fn day04(rng: &mut Rng, size: Size) -> String {
+    let mut out = String::new();
+    let top = size.either(9, 99);
+    for _ in 0..size.scaled(6, 1000) {
+        let mut range = || {
+            let lo = rng.range(1, top);
+            (lo, rng.range(lo, top))
+        };
+        let ((a, b), (c, d)) = (range(), range());
+        writeln!(out, "{}-{},{}-{}", a, b, c, d).unwrap();
+    }
+    out
+}
+
+// Nine stacks (three for an example), with 50 crates and 500 moves per
+// scale. The moves never empty a stack, so every stack has a crate on top at
+// the end.
+fn day05(rng: &mut Rng, size: Size) -> String {
+    let count = size.either(3, 9);
+    let mut stacks: Vec<Vec<u8>> = (0..count).map(|_| vec![rng.pick(LOWER).to_ascii_uppercase()]).collect();
+    for _ in count..size.scaled(6, 50) {
+        let stack = rng.below(count);
+        stacks[stack].push(rng.pick(LOWER).to_ascii_uppercase());
+    }
+
+    let mut out = String::new();
+    let height = stacks.iter().map(Vec::len).max().unwrap();
+    for row in (0..height).rev() {
+        let cells: Vec<String> = stacks
+            .iter()
+            .map(|stack| match stack.get(row) {
+                Some(&container) => format!("[{}]", container as char),
+                None => "   ".to_string(),
+            })
+            .collect();
+        writeln!(out, "{}", cells.join(" ").trim_end()).unwrap();
+    }
+    let numbers: Vec<String> = (1..=count).map(|n| format!(" {} ", n)).collect();
+    writeln!(out, "{}\n", numbers.join(" ").trim_end()).unwrap();
+
+    let mut sizes: Vec<usize> = stacks.iter().map(Vec::len).collect();
+    let mut moves = 0;
+    while moves < size.scaled(4, 500) {
+        let (src, dst) = (rng.below(count), rng.below(count));
+        if src == dst || sizes[src] < 2 {
+            continue;
+        }
+        let num = rng.range(1, sizes[src] as i64 - 1) as usize;
+        sizes[src] -= num;
+        sizes[dst] += num;
+        writeln!(out, "move {} from {} to {}", num, src + 1, dst + 1).unwrap();
+        moves += 1;
+    }
+    out
+}
+
+// 4096 characters per scale. Only the first eight letters show up until one
+// run of fourteen different letters, so the start-of-message marker is late.
+fn day06(rng: &mut Rng, size: Size) -> String {
+    let len = size.scaled(30, 4096);
+    let marker_at = rng.below(len - 14);
+    let mut out: Vec<u8> = (0..marker_at).map(|_| rng.pick(&LOWER[..8])).collect();
+    let mut marker = LOWER.to_vec();
+    rng.shuffle(&mut marker);
+    out.extend(&marker[..14]);
+    out.extend((out.len()..len).map(|_| rng.pick(LOWER)));
+    String::from_utf8(out).unwrap()
+}
+
+// A directory tree with about 200 directories per scale, filling between 40
+// and 70 MB of the 70 MB disk so there's always something to delete.
+fn day07(rng: &mut Rng, size: Size) -> String {
+    struct Dir {
+        subdirs: Vec<usize>,
+        files: Vec<u64>,
+    }
+    let mut dirs = vec![Dir { subdirs: vec![], files: vec![] }];
+    for i in 1..size.scaled(4, 200) {
+        let parent = rng.below(i);
+        dirs[parent].subdirs.push(i);
+        dirs.push(Dir { subdirs: vec![], files: vec![] });
+    }
+    let mut weights = Vec::new();
+    for dir in &mut dirs {
+        for _ in 0..rng.range(0, 6) {
+            let weight = rng.range(1, 1000) as u64;
+            dir.files.push(weight);
+            weights.push(weight);
+        }
+    }
+    let target = rng.range(45_000_000, 65_000_000) as u64;
+    let scale_by = target / weights.iter().sum::<u64>().max(1);
+
+    fn walk(rng: &mut Rng, dirs: &[Dir], i: usize, scale_by: u64, out: &mut String) {
+        out.push_str("$ ls\n");
+        for (n, _) in dirs[i].subdirs.iter().enumerate() {
+            writeln!(out, "dir d{}", n).unwrap();
+        }
+        for (n, weight) in dirs[i].files.iter().enumerate() {
+            let ext = rng.pick(&["", ".txt", ".dat", ".log"]);
+            writeln!(out, "{} f{}{}", weight * scale_by, n, ext).unwrap();
+        }
+        for (n, &sub) in dirs[i].subdirs.iter().enumerate() {
+            writeln!(out, "$ cd d{}", n).unwrap();
+            walk(rng, dirs, sub, scale_by, out);
+            out.push_str("$ cd ..\n");
+        }
+    }
+    let mut out = String::from("$ cd /\n");
+    walk(rng, &dirs, 0, scale_by, &mut out);
+    out
+}
+
+// A 99x99 forest, with sides that grow with the square root of the scale.
+fn day08(rng: &mut Rng, size: Size) -> String {
+    let side = match size {
+        Size::Example => 5,
+        Size::Full(scale) => (99.0 * (scale as f64).sqrt()) as usize,
+    };
+    let mut out = String::with_capacity(side * (side + 1));
+    for _ in 0..side {
+        for _ in 0..side {
+            out.push((b'0' + rng.below(10) as u8) as char);
+        }
+        out.push('\n');
+    }
+    out
+}
+
+// 2000 moves per scale.
+fn day09(rng: &mut Rng, size: Size) -> String {
+    let mut out = String::new();
+    for _ in 0..size.scaled(8, 2000) {
+        writeln!(out, "{} {}", rng.pick(&['R', 'L', 'U', 'D']), rng.range(1, size.either(5, 20))).unwrap();
+    }
+    out
+}
+
+// Always exactly the 240 cycles the CRT has room for, whatever the size. X
+// wanders around the screen.
+fn day10(rng: &mut Rng, _size: Size) -> String {
+    let mut out = String::new();
+    let (mut cycles, mut x) = (0, 1);
+    while cycles < 240 {
+        if cycles == 239 || rng.chance(30) {
+            out.push_str("noop\n");
+            cycles += 1;
+        } else {
+            let value = rng.range(-(x + 1).min(10), (40 - x).min(10));
+            writeln!(out, "addx {}", value).unwrap();
+            x += value;
+            cycles += 2;
+        }
+    }
+    out
+}
+
+struct Monkey {
+    items: Vec<i32>,
+    add: bool,
+    // None for "old".
+    value: Option<i32>,
+    divisor: i32,
+    if_true: usize,
+    if_false: usize,
+}
+
+// Does part 1's arithmetic overflow? It has no modulus to keep the worry
+// levels down.
+fn monkeys_overflow(monkeys: &[Monkey]) -> bool {
+    let mut has: Vec<Vec<i32>> = monkeys.iter().map(|monkey| monkey.items.clone()).collect();
+    for _ in 0..20 {
+        for (i, monkey) in monkeys.iter().enumerate() {
+            for item in std::mem::take(&mut has[i]) {
+                let rhs = monkey.value.unwrap_or(item);
+                let Some(item) = (if monkey.add { item.checked_add(rhs) } else { item.checked_mul(rhs) }) else {
+                    return true;
+                };
+                let item = item / 3;
+                has[if item % monkey.divisor == 0 { monkey.if_true } else { monkey.if_false }].push(item);
+            }
+        }
+    }
+    false
+}
+
+// Four monkeys plus one per scale, up to nine (any more and the product of
+// the divisors wouldn't fit), with more items as the scale grows.
+fn day11(rng: &mut Rng, size: Size) -> String {
+    let count = match size {
+        Size::Example => 4,
+        Size::Full(scale) => (4 + scale).min(9),
+    };
+    let most_items = size.scaled(3, 4) as i64;
+    let mut primes = vec![2, 3, 5, 7, 11, 13, 17, 19, 23];
+    rng.shuffle(&mut primes);
+
+    let monkeys = loop {
+        let monkeys: Vec<_> = (0..count)
+            .map(|i| {
+                let items = (0..rng.range(1, most_items)).map(|_| rng.range(40, 99) as i32).collect();
+                let add = rng.chance(50);
+                let value = if rng.chance(10) { None } else { Some(rng.range(1, if add { 9 } else { 19 }) as i32) };
+                let mut target = || loop {
+                    let target = rng.below(count);
+                    if target != i {
+                        break target;
+                    }
+                };
+                let (if_true, if_false) = (target(), target());
+                Monkey { items, add, value, divisor: primes[i], if_true, if_false }
+            })
+            .collect();
+        if !monkeys_overflow(&monkeys) {
+            break monkeys;
+        }
+    };
+
+    let mut out = String::new();
+    for (i, monkey) in monkeys.iter().enumerate() {
+        let items: Vec<String> = monkey.items.iter().map(i32::to_string).collect();
+        let value = monkey.value.map_or("old".to_string(), |value| value.to_string());
+        if i > 0 {
+            out.push('\n');
+        }
+        writeln!(out, "Monkey {}:", i).unwrap();
+        writeln!(out, "  Starting items: {}", items.join(", ")).unwrap();
+        writeln!(out, "  Operation: new = old {} {}", if monkey.add { '+' } else { '*' }, value).unwrap();
+        writeln!(out, "  Test: divisible by {}", monkey.divisor).unwrap();
+        writeln!(out, "    If true: throw to monkey {}", monkey.if_true).unwrap();
+        writeln!(out, "    If false: throw to monkey {}", monkey.if_false).unwrap();
+    }
+    out
+}
+
+// A 41x170 hill per scale (rows grow with the scale), rising a letter at a
+// time from west to east, and pitted with low spots. One row is left clear so
+// there's always a way from S to E.
+fn day12(rng: &mut Rng, size: Size) -> String {
+    let (rows, cols) = (size.scaled(5, 41), size.either(26, 170));
+    let clear = rng.below(rows);
+    let mut out = String::with_capacity(rows * (cols + 1));
+    for r in 0..rows {
+        for c in 0..cols {
+            let height = (c * 25 / (cols - 1)) as u8;
+            let ch = if r == clear && c == 0 {
+                'S'
+            } else if r == clear && c == cols - 1 {
+                'E'
+            } else if r != clear && rng.chance(30) {
+                (b'a' + rng.below(height as usize + 1) as u8) as char
+            } else {
+                (b'a' + height) as char
+            };
+            out.push(ch);
+        }
+        out.push('\n');
+    }
+    out
+}
+
+fn packet(rng: &mut Rng, depth: usize, out: &mut String) {
+    if depth == 0 || rng.chance(40) {
+        write!(out, "{}", rng.range(0, 10)).unwrap();
+        return;
+    }
+    out.push('[');
+    for i in 0..rng.below(5) {
+        if i > 0 {
+            out.push(',');
+        }
+        packet(rng, depth - 1, out);
+    }
+    out.push(']');
+}
+
+// 150 pairs of packets per scale, nested up to 4 + the scale deep. The two
+// packets in a pair start with different numbers, so they're never equal, and
+// neither can be a divider packet.
+fn day13(rng: &mut Rng, size: Size) -> String {
+    let depth = match size {
+        Size::Example => 2,
+        Size::Full(scale) => 4 + scale,
+    };
+    let mut out = String::new();
+    for pair in 0..size.scaled(8, 150) {
+        if pair > 0 {
+            out.push('\n');
+        }
+        let first = rng.range(0, 10);
+        let second = (first + rng.range(1, 10)) % 11;
+        for start in [first, second] {
+            write!(out, "[{}", start).unwrap();
+            for _ in 0..rng.below(6) {
+                out.push(',');
+                packet(rng, depth, &mut out);
+            }
+            out.push_str("]\n");
+        }
+    }
+    out
+}
+
+// 150 paths of rock per scale, in a cave 170 deep per scale.
+fn day14(rng: &mut Rng, size: Size) -> String {
+    let depth = size.scaled(12, 170) as i64;
+    let mut out = String::new();
+    for _ in 0..size.scaled(4, 150) {
+        let (mut x, mut y) = (rng.range(500 - depth, 500 + depth), rng.range(2, depth));
+        let mut points = vec![format!("{},{}", x, y)];
+        for i in 0..rng.range(1, 6) {
+            let step = rng.range(-6, 6);
+            if i % 2 == 0 {
+                x += step;
+            } else {
+                y = (y + step).max(1);
+            }
+            points.push(format!("{},{}", x, y));
+        }
+        writeln!(out, "{}", points.join(" -> ")).unwrap();
+    }
+    out
+}
+
+type Pt = (i64, i64);
+
+fn l1(a: Pt, b: Pt) -> i64 {
+    (a.0 - b.0).abs() + (a.1 - b.1).abs()
+}
+
+struct Sensor {
+    at: Pt,
+    reach: i64,
+}
+
+// The first spot from row `y` on, row by row, that no sensor reaches, other
+// than the distress beacon. The edges of what's covered move at most a step
+// each row, so rows that are sure to be covered still are skipped.
+fn find_gap(sensors: &[Sensor], distress: Pt, limit: i64, mut y: i64) -> Option<Pt> {
+    while y <= limit {
+        let mut spans: Vec<Pt> = sensors
+            .iter()
+            .filter_map(|s| {
+                let half = s.reach - (y - s.at.1).abs();
+                (half >= 0).then_some((s.at.0 - half, s.at.0 + half))
+            })
+            .collect();
+        spans.sort_unstable();
+        let mut spans = spans.into_iter().peekable();
+
+        // How many more rows the spans used so far are sure to cover.
+        let (mut x, mut safe) = (0, i64::MAX);
+        while x <= limit {
+            match spans.peek() {
+                Some(&(lo, hi)) if lo <= x => {
+                    if hi >= x {
+                        // Both this span's start and the last one's end can
+                        // move a step towards each other.
+                        safe = safe.min((x - lo) / 2);
+                        x = hi + 1;
+                    }
+                    spans.next();
+                }
+                _ if (x, y) == distress => {
+                    x += 1;
+                    safe = 0;
+                }
+                _ => return Some((x, y)),
+            }
+        }
+        y += safe.min(x - 1 - limit) + 1;
+    }
+    None
+}
+
+// A sensor somewhere around `target` that reaches it, but not the distress
+// beacon, if a random spot works out.
+fn sensor_near(rng: &mut Rng, distress: Pt, limit: i64, target: Pt) -> Option<Sensor> {
+    let most = rng.range(limit / 10, limit / 3).max(1);
+    let away = rng.range(0, most);
+    let across = rng.range(-away, away);
+    let at = (target.0 + across, target.1 + (away - across.abs()) * if rng.chance(50) { 1 } else { -1 });
+    let reach = most.min(l1(at, distress) - 1);
+    (reach >= away.max(1)).then_some(Sensor { at, reach })
+}
+
+// Spots on the edge of `sensors[i]`'s reach that no other sensor reaches, as
+// ranges of (start, step, count). The edge is four diagonal runs, and each
+// other sensor reaches one stretch of each run.
+fn free_edge(sensors: &[Sensor], i: usize) -> Vec<(Pt, Pt, i64)> {
+    let Sensor { at: (x, y), reach: r } = sensors[i];
+    let runs = [((x + r, y), (-1, 1)), ((x, y + r), (-1, -1)), ((x - r, y), (1, -1)), ((x, y - r), (1, 1))];
+    let mut free = Vec::new();
+    for (start, step) in runs {
+        let mut reached: Vec<Pt> = sensors
+            .iter()
+            .enumerate()
+            .filter(|&(j, _)| j != i)
+            .filter_map(|(_, other)| {
+                // The distance from `other` after t steps is |t - a| + |t - b|.
+                let a = step.0 * (other.at.0 - start.0);
+                let b = step.1 * (other.at.1 - start.1);
+                ((a - b).abs() <= other.reach)
+                    .then_some(((a + b - other.reach + 1).div_euclid(2), (a + b + other.reach).div_euclid(2)))
+            })
+            .collect();
+        reached.sort_unstable();
+        let mut t = 0;
+        for (lo, hi) in reached.into_iter().chain([(r, r)]) {
+            if lo > t {
+                let first = (start.0 + t * step.0, start.1 + t * step.1);
+                free.push((first, step, lo.min(r) - t));
+            }
+            t = t.max(hi + 1);
+            if t >= r {
+                break;
+            }
+        }
+    }
+    free
+}
+
+// Sensors that between them reach everywhere in the square but the one spot
+// for the distress beacon, added around whatever gap is left until there
+// isn't one. Each beacon goes on its sensor's edge where no other sensor
+// reaches, if there's anywhere, so it's the only closest beacon. Otherwise
+// the sensor is dropped if the others cover for it, or else its beacon just
+// keeps off the row part 1 looks at, which is all the answers depend on.
+fn day15_in(rng: &mut Rng, limit: i64) -> String {
+    let distress = (rng.range(0, limit), rng.range(0, limit));
+    let mut sensors = Vec::new();
+    while let Some(gap) = find_gap(&sensors, distress, limit, 0) {
+        sensors.extend(sensor_near(rng, distress, limit, gap));
+    }
+    rng.shuffle(&mut sensors);
+
+    let mut beacons = Vec::new();
+    let mut i = 0;
+    while i < sensors.len() {
+        let free = free_edge(&sensors, i);
+        if free.is_empty() {
+            let sensor = sensors.remove(i);
+            if find_gap(&sensors, distress, limit, 0).is_none() {
+                continue;
+            }
+            sensors.insert(i, sensor);
+        }
+        let Sensor { at, reach } = sensors[i];
+        let beacon = if free.is_empty() {
+            // A sensor on the row can't have its beacon straight across.
+            let wide = if at.1 == limit / 2 { reach - 1 } else { reach };
+            let across = rng.range(-wide, wide);
+            let down = reach - across.abs();
+            let down = if at.1 + down == limit / 2 || (at.1 - down != limit / 2 && rng.chance(50)) { -down } else { down };
+            (at.0 + across, at.1 + down)
+        } else {
+            let (first, step, count) = free[rng.below(free.len())];
+            let t = rng.range(0, count - 1);
+            (first.0 + t * step.0, first.1 + t * step.1)
+        };
+        beacons.push(beacon);
+        i += 1;
+    }
+
+    let mut out = String::new();
+    for (sensor, beacon) in sensors.iter().zip(beacons) {
+        let at = sensor.at;
+        writeln!(out, "Sensor at x={}, y={}: closest beacon is at x={}, y={}", at.0, at.1, beacon.0, beacon.1).unwrap();
+    }
+    out
+}
+
+// The puzzle's 4,000,000 square, however big the scale, or the example's 20.
+fn day15(rng: &mut Rng, size: Size) -> String {
+    day15_in(rng, size.either(20, 4_000_000))
+}
+
+// Valves in a connected cave: 8 + 2 per scale with flow, and 20 per scale
+// without. The search grows quickly with the flowing valves.
+fn day16(rng: &mut Rng, size: Size) -> String {
+    let flowing = match size {
+        Size::Example => 6,
+        Size::Full(scale) => 8 + 2 * scale,
+    };
+    let count = (flowing + size.scaled(5, 20)).min(26 * 26);
+    let mut names = BTreeSet::from(["AA".to_string()]);
+    while names.len() < count {
+        names.insert(format!("{}{}", rng.pick(LOWER) as char, rng.pick(LOWER) as char).to_uppercase());
+    }
+    let mut names: Vec<String> = names.into_iter().collect();
+    rng.shuffle(&mut names);
+
+    // A random tree, so everything is reachable, plus some shortcuts.
+    let mut tunnels = vec![BTreeSet::new(); count];
+    for i in 1..count {
+        let j = rng.below(i);
+        tunnels[i].insert(j);
+        tunnels[j].insert(i);
+    }
+    for _ in 0..count / 3 {
+        let (i, j) = (rng.below(count), rng.below(count));
+        if i != j {
+            tunnels[i].insert(j);
+            tunnels[j].insert(i);
+        }
+    }
+
+    let mut out = String::new();
+    for (i, name) in names.iter().enumerate() {
+        let flow = if i < flowing && name != "AA" { rng.range(1, 25) } else { 0 };
+        let to: Vec<&str> = tunnels[i].iter().map(|&j| names[j].as_str()).collect();
+        let lead = if to.len() == 1 { "tunnel leads to valve" } else { "tunnels lead to valves" };
+        writeln!(out, "Valve {} has flow rate={}; {} {}", name, flow, lead, to.join(", ")).unwrap();
+    }
+    out
+}
+
+// 2000 jets per scale.
+fn day17(rng: &mut Rng, size: Size) -> String {
+    (0..size.scaled(40, 2000)).map(|_| rng.pick(&['<', '>'])).collect()
+}
+
+// A lumpy droplet in a cube 20 across per scale.
+fn day18(rng: &mut Rng, size: Size) -> String {
+    let side = size.scaled(6, 20) as i64;
+    let mid = side / 2;
+    let mut out = String::new();
+    for x in 1..=side {
+        for y in 1..=side {
+            for z in 1..=side {
+                let dist = ((x - mid).pow(2) + (y - mid).pow(2) + (z - mid).pow(2)) as f64;
+                if dist.sqrt() < mid as f64 && rng.chance(60) {
+                    writeln!(out, "{},{},{}", x, y, z).unwrap();
+                }
+            }
+        }
+    }
+    out
+}
+
+// A random input for the day. The same seed always gives the same input.
+pub fn generate(day: u32, size: Size, seed: u64) -> Option<String> {
+    let generator = match day {
+        1 => day01,
+        2 => day02,
+        3 => day03,
+        4 => day04,
+        5 => day05,
+        6 => day06,
+        7 => day07,
+        8 => day08,
+        9 => day09,
+        10 => day10,
+        11 => day11,
+        12 => day12,
+        13 => day13,
+        14 => day14,
+        15 => day15,
+        16 => day16,
+        17 => day17,
+        18 => day18,
+        _ => return None,
+    };
+    let mut rng = Rng::new(seed ^ day as u64);
+    Some(generator(&mut rng, size))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::Parts;
+
+    #[test]
+    fn seeds_are_repeatable() {
+        assert_eq!(generate(5, Size::Full(1), 7), generate(5, Size::Full(1), 7));
+        assert_ne!(generate(5, Size::Full(1), 7), generate(5, Size::Full(1), 8));
+    }
+
+    #[test]
+    fn generated_inputs_solve() {
+        for solution in crate::DAYS {
+            for (size, seed) in [(Size::Full(1), 0), (Size::Full(1), 1), (Size::Example, 0)] {
+                // A day fresh from `new-day` has no generator yet.
+                let Some(input) = generate(solution.day(), size, seed) else {
+                    continue;
+                };
+                let input = input.trim_end();
+                assert_eq!(solution.lint(input), vec![], "day {} {:?} seed {}", solution.day(), size, seed);
+                // Day 16's search is too slow for a real-sized input in a debug build.
+                if solution.day() == 16 && size != Size::Example {
+                    continue;
+                }
+                solution.solve(input, size == Size::Example, Parts::Both).unwrap();
+                // The references check the puzzles' promises (day 15's one
+                // spot for the distress beacon, say) as they go. Most are too
+                // slow for a real-sized input in a debug build.
+                if size == Size::Example {
+                    solution.solve_reference(input, true, Parts::Both).unwrap();
+                }
+            }
+        }
+    }
+
+    // Day 15's reference checks every row of the real-sized square for the
+    // one spot.
+    #[test]
+    fn day15_real_size_has_one_spot() {
+        let input = generate(15, Size::Full(1), 0).unwrap();
+        crate::find_day(15).unwrap().solve_reference(input.trim_end(), false, Parts::Only(2)).unwrap();
+    }
+
+    // Day 15's promises, checked spot by spot in a square small enough to:
+    // one spot that no sensor reaches, and no beacon on the probed row that a
+    // sensor is closer to than its own.
+    #[test]
+    fn day15_leaves_one_spot() {
+        for seed in 0..20 {
+            let input = day15_in(&mut Rng::new(seed), 20);
+            let pairs: Vec<(Pt, Pt)> = input
+                .lines()
+                .map(|line| {
+                    let nums: Vec<i64> = line
+                        .split(|c: char| c != '-' && !c.is_ascii_digit())
+                        .filter(|num| !num.is_empty())
+                        .map(|num| num.parse().unwrap())
+                        .collect();
+                    ((nums[0], nums[1]), (nums[2], nums[3]))
+                })
+                .collect();
+            let unseen = (0..=20)
+                .flat_map(|y| (0..=20).map(move |x| (x, y)))
+                .filter(|&spot| pairs.iter().all(|&(sensor, beacon)| l1(sensor, spot) > l1(sensor, beacon)))
+                .count();
+            assert_eq!(unseen, 1, "seed {}", seed);
+            for &(_, probed) in pairs.iter().filter(|(_, beacon)| beacon.1 == 10) {
+                assert!(pairs.iter().all(|&(sensor, beacon)| beacon == probed || l1(sensor, probed) > l1(sensor, beacon)), "seed {}", seed);
+            }
+        }
+    }
+}
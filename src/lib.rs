@@ -0,0 +1,31 @@
+// Solutions for Advent of Code 2022, and the tools for running and timing them.
+
+pub mod alloc;
+pub mod answers;
+pub mod baseline;
+pub mod batch;
+pub mod bench;
+pub mod budget;
+pub mod differential;
+pub mod gen;
+pub mod lint;
+pub mod ocr;
+pub mod out;
+pub mod readme;
+pub mod report;
+pub mod runner;
+pub mod scaffold;
+pub mod select;
+pub mod site;
+pub mod solution;
+pub mod submit;
+pub mod utils;
+
+use solution::DynSolution;
+
+// The dayNN modules and DAYS, found by build.rs.
+include!(concat!(env!("OUT_DIR"), "/days.rs"));
+
+pub fn find_day(day: u32) -> Option<&'static dyn DynSolution> {
+    DAYS.iter().copied().find(|solution| solution.day() == day)
+}
@@ -0,0 +1,152 @@
+use crate::solution::DynSolution;
+
+// Something wrong with an input, on a (1-based) line.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Problem {
+    pub line: usize,
+    pub message: String,
+}
+
+// Everything wrong with a day's raw input: CRLF line endings, then whatever
+// the day's grammar turns up, in line order. Trailing whitespace only counts
+// where it trips up the grammar, since some inputs (day 5's drawing) have it
+// on purpose.
+pub fn lint(solution: &dyn DynSolution, raw: &str) -> Vec<Problem> {
+    if raw.trim().is_empty() {
+        return vec![Problem { line: 1, message: "the input is empty".to_string() }];
+    }
+
+    let mut problems = Vec::new();
+    let lines: Vec<&str> = raw.split('\n').collect();
+    let crlf: Vec<usize> = (1..=lines.len()).filter(|&n| lines[n - 1].ends_with('\r')).collect();
+    if let Some(&first) = crlf.first() {
+        problems.push(Problem {
+            line: first,
+            message: format!("line {}: CRLF line endings, on {} lines", first, crlf.len()),
+        });
+    }
+
+    let text: Vec<&str> = lines.iter().map(|line| line.strip_suffix('\r').unwrap_or(line)).collect();
+    let text = text.join("\n");
+    for err in solution.lint(text.trim_end()) {
+        let line = text.lines().nth(err.line - 1).unwrap_or("");
+        let content = line.trim_end().chars().count();
+        let message = if content < line.chars().count() && err.column > content {
+            format!("line {}: trailing whitespace", err.line)
+        } else {
+            err.to_string()
+        };
+        problems.push(Problem { line: err.line, message });
+    }
+    problems.sort_by_key(|problem| problem.line);
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::find_day;
+
+    fn messages(day: u32, raw: &str) -> Vec<String> {
+        lint(find_day(day).unwrap(), raw).into_iter().map(|problem| problem.message).collect()
+    }
+
+    #[test]
+    fn examples_are_clean() {
+        // A day fresh from `new-day` has an empty example until it's filled in.
+        for solution in crate::DAYS.iter().filter(|solution| !solution.example().is_empty()) {
+            // Day 15's example is in the same format as the real input, just smaller.
+            assert_eq!(lint(*solution, solution.example()), vec![], "day {}", solution.day());
+        }
+    }
+
+    #[test]
+    fn reports_every_bad_line() {
+        let problems = messages(2, "A Y\r\nB Q\r\nC Z \r\nD X\r\n");
+        assert_eq!(problems.len(), 4);
+        assert_eq!(problems[0], "line 1: CRLF line endings, on 4 lines");
+        assert!(problems[1].starts_with("day 2, line 2, column 3: expected one of \"X\", \"Y\", \"Z\", found \"Q\""));
+        assert_eq!(problems[2], "line 3: trailing whitespace");
+        assert!(problems[3].starts_with("day 2, line 4, column 1:"));
+    }
+
+    #[test]
+    fn jets_report_each_bad_character() {
+        let problems = messages(17, "<<x>>y<");
+        assert_eq!(problems.len(), 2);
+        assert!(problems[0].contains("column 3"));
+        assert!(problems[1].contains("column 6"));
+    }
+
+    #[test]
+    fn bad_monkey_does_not_hide_the_next() {
+        let monkeys = "Monkey 0:
+  Starting items: 79, 98
+  Operation: new = old ^ 19
+  Test: divisible by 23
+    If true: throw to monkey 1
+    If false: throw to monkey 3
+
+Monkey 1:
+  Starting items: 54
+  Operation: new = old + 6
+  Test: divisible by 19
+    If true: throw to monkey 2
+    If false: throw to monkey 0";
+        let problems = messages(11, monkeys);
+        // The rest of monkey 0 is skipped, so its bad target isn't seen.
+        assert_eq!(problems.len(), 2, "{:?}", problems);
+        assert!(problems[0].starts_with("day 11, line 3, column 24: expected one of \"+\", \"*\""));
+        assert!(problems[1].starts_with("day 11, line 12, column 30: expected a monkey from 0 to 1"));
+    }
+
+    #[test]
+    fn monkeys_throw_to_real_monkeys() {
+        let monkeys = "Monkey 0:
+  Starting items: 79, 98
+  Operation: new = old * 19
+  Test: divisible by 23
+    If true: throw to monkey -1
+    If false: throw to monkey 1
+
+Monkey 1:
+  Starting items: 54
+  Operation: new = old + 6
+  Test: divisible by 19
+    If true: throw to monkey 2
+    If false: throw to monkey 0";
+        let problems = messages(11, monkeys);
+        assert_eq!(problems.len(), 2, "{:?}", problems);
+        assert!(problems[0].starts_with("day 11, line 5, column 30: expected a number that fits in usize"));
+        assert!(problems[1].starts_with("day 11, line 12, column 30: expected a monkey from 0 to 1"));
+    }
+
+    #[test]
+    fn monkeys_divide_by_more_than_zero() {
+        let monkeys = "Monkey 0:
+  Starting items: 79, 98
+  Operation: new = old * 19
+  Test: divisible by 0
+    If true: throw to monkey 0
+    If false: throw to monkey 0";
+        let problems = messages(11, monkeys);
+        assert_eq!(problems.len(), 1, "{:?}", problems);
+        assert!(problems[0].starts_with("day 11, line 4, column 22: expected a number above 0"));
+    }
+
+    #[test]
+    fn moves_fit_the_stacks() {
+        let crates = "[D]
+[N] [C]
+ 1   2
+
+move 1 from 2 to 1
+move 4 from 1 to 2
+move -1 from 1 to 2
+move 3 from 1 to 2";
+        let problems = messages(5, crates);
+        assert_eq!(problems.len(), 2, "{:?}", problems);
+        assert!(problems[0].starts_with("day 5, line 6, column 6: expected at most 3 crates, as stack 1 has"));
+        assert!(problems[1].starts_with("day 5, line 7, column 6: expected a number that fits in usize"));
+    }
+}
@@ -1,112 +1,693 @@
-use std::time::Instant;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::hint::black_box;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
-use clap::Parser;
+use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum};
 use thousands::Separable;
 
+use advent21::alloc::{self, AllocStats, CountingAlloc};
+use advent21::answers::{AnswerFile, Check, EXAMPLE_USER};
+use advent21::baseline::Baseline;
+use advent21::batch;
+use advent21::bench::{bench, fmt_micros, BenchConfig};
+use advent21::budget::Budget;
+use advent21::differential;
+use advent21::gen;
+use advent21::lint;
+use advent21::ocr::read_screen;
+use advent21::out;
+use advent21::readme;
+use advent21::report::{DayReport, PartReport, Report, Timing};
+use advent21::runner::{parse_failure, run_day, run_day_limited, run_day_timed, run_days_parallel, PhaseAllocs, Phases};
+use advent21::scaffold;
+use advent21::select::DaySelection;
+use advent21::site::{self, Client, Fetched, Session};
+use advent21::solution::{Answer, DynSolution, Parts};
+use advent21::submit::{self, Guesses, Hint, Verdict};
+use advent21::utils::{InputSource, ParseError};
+use advent21::{say, DAYS};
+
+// Only counts anything with --alloc-stats.
+#[global_allocator]
+static ALLOC: CountingAlloc = CountingAlloc;
+
+// Most samples --per takes of a day, however fast it is.
+const MAX_SAMPLES: usize = 100_000;
+
+// A number of seconds for an option, which has to fit in a `Duration`.
+fn seconds(arg: &str) -> Result<f32, String> {
+    let secs: f32 = arg.parse().map_err(|err| format!("{}", err))?;
+    if secs.is_finite() && secs >= 0.0 {
+        Ok(secs)
+    } else {
+        Err("expected a number of seconds, 0 or more".to_string())
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    Text,
+    Json,
+    Csv,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Benchmarks every day like --per, and rewrites the timing table in the README.
+    Readme {
+        /// The README whose timing table to rewrite.
+        #[clap(default_value = "README.md")]
+        path: PathBuf,
+    },
+    /// Starts a new day from a template, with an empty entry for its example answers.
+    NewDay {
+        #[clap(value_parser = clap::value_parser!(u32).range(1..=25))]
+        day: u32,
+    },
+    /// Downloads a day's input into the input directory, unless it's already there.
+    Fetch {
+        #[clap(value_parser = clap::value_parser!(u32).range(1..=25))]
+        day: u32,
+        /// Waits for the puzzle to unlock (midnight US Eastern) first.
+        #[clap(short, long)]
+        wait: bool,
+        #[clap(flatten)]
+        site: SiteOpts,
+    },
+    /// Checks the days' inputs (or examples, with -t) for everything that won't parse.
+    Lint,
+    /// Runs one part of a day on its input and sends the answer.
+    Submit {
+        #[clap(value_parser = clap::value_parser!(u32).range(1..=25))]
+        day: u32,
+        #[clap(value_parser = clap::value_parser!(u32).range(1..=2))]
+        part: u32,
+        #[clap(flatten)]
+        site: SiteOpts,
+    },
+    /// Prints a random input for a day, which is the same every time for the same seed.
+    Gen {
+        #[clap(value_parser = clap::value_parser!(u32).range(1..=25))]
+        day: u32,
+        /// Roughly how many times bigger than a real input.
+        #[clap(long, default_value = "1", value_parser = clap::value_parser!(u64).range(1..))]
+        scale: u64,
+        /// About the size of the puzzle's example instead, for running with -t.
+        #[clap(long, conflicts_with = "scale")]
+        example: bool,
+        /// Picks a different input; the same seed always gives the same one.
+        #[clap(long, default_value = "0")]
+        seed: u64,
+    },
+    /// Checks the days' parts against their slow reference versions on generated
+    /// inputs, shrinking the input for any answer they disagree on.
+    Differential {
+        /// How many inputs (seeds 0 up) to try for each day.
+        #[clap(long, default_value = "100")]
+        seeds: u64,
+        /// Real-sized inputs, times this, instead of example-sized ones.
+        #[clap(long, value_parser = clap::value_parser!(u64).range(1..))]
+        scale: Option<u64>,
+    },
+}
+
+#[derive(Args)]
+struct SiteOpts {
+    /// Holds the session cookie, unless it's in $AOC_SESSION.
+    #[clap(long, default_value = ".session")]
+    session_file: PathBuf,
+    /// Where the site is, to use a local test server instead.
+    #[clap(long, default_value = site::DEFAULT_BASE_URL)]
+    base_url: String,
+}
 
 #[derive(Parser)]
 struct Opts {
-    #[clap(short, default_value="1")]
+    #[clap(subcommand)]
+    command: Option<Command>,
+    #[clap(short, default_value="1", conflicts_with = "atleast")]
     repeat: i32,
-    day: Option<i32>,
-    #[clap(long)]
+    #[clap(conflicts_with = "days")]
+    day: Option<u32>,
+    /// Days to run, like "1-10", "12,15,17" or "!16" (everything but 16).
+    #[clap(long, global = true)]
+    days: Option<DaySelection>,
+    /// Roughly how many seconds --per samples each day for (half a second by default).
+    #[clap(long, value_parser = seconds)]
     atleast: Option<f32>,
+    /// Benchmarks each day separately.
     #[clap(long)]
     per: bool,
-    #[clap(short, long)]
+    /// Seconds of warmup per day before --per starts sampling.
+    #[clap(long, default_value = "0.1", value_parser = seconds)]
+    warmup: f32,
+    /// Fewest samples --per takes, even for slow days.
+    #[clap(long, default_value = "5", value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..=MAX_SAMPLES as u64))]
+    min_samples: usize,
+    /// Writes the --per results as a CSV baseline.
+    #[clap(long)]
+    save_baseline: Option<PathBuf>,
+    /// Benchmarks per-day, and compares against a saved baseline.
+    #[clap(long)]
+    compare: Option<PathBuf>,
+    /// Benchmarks per-day, and fails if any day (or the total) is over its budget.
+    #[clap(long)]
+    check_budget: bool,
+    /// How long each day may take, for --check-budget.
+    #[clap(long, default_value = "budget.toml")]
+    budget: PathBuf,
+    /// Percent slowdown that --compare counts as a regression.
+    #[clap(long, default_value = "10")]
+    threshold: f64,
+    #[clap(short, long, global = true)]
     test: bool,
+    /// Runs the days in parallel on this many threads.
+    #[clap(long, conflicts_with_all = ["per", "compare", "check_budget"], value_parser = clap::value_parser!(u32).range(1..))]
+    jobs: Option<u32>,
+    /// Gives up on any day that takes longer than this many seconds.
+    #[clap(long, conflicts_with = "jobs", value_parser = seconds)]
+    timeout: Option<f32>,
+    /// Counts heap allocations in each phase of each day.
+    #[clap(long, conflicts_with = "jobs")]
+    alloc_stats: bool,
+    /// Runs (and times) only this part.
+    #[clap(long, value_parser = clap::value_parser!(u32).range(1..=2))]
+    part: Option<u32>,
+    /// Whose answers to check against (answers/<user>.toml). Defaults to $USER, or "default".
+    #[clap(long, global = true)]
+    user: Option<String>,
+    /// Writes any answers that aren't known yet into the answers file.
+    #[clap(long)]
+    record: bool,
+    /// Input file for a single day, or "-" to read stdin.
+    #[clap(long, requires = "day", conflicts_with_all = ["input_dir", "test", "per", "compare", "check_budget", "batch"])]
+    input: Option<PathBuf>,
+    /// Directory holding inputNN.txt files.
+    #[clap(long, default_value = "inputs", global = true)]
+    input_dir: PathBuf,
+    /// Runs against every <dir>/<user>/inputNN.txt, checking each user's answers.
+    #[clap(long)]
+    batch: Option<PathBuf>,
+    /// Prints the answers and timings as JSON or CSV on stdout. Everything
+    /// else goes to stderr.
+    #[clap(long, value_enum, default_value = "text", conflicts_with = "batch")]
+    format: Format,
+}
+
+fn fetch(day: u32, wait: bool, site_opts: &SiteOpts, dir: &Path) -> Result<(), Box<dyn Error>> {
+    let path = dir.join(format!("input{:02}.txt", day));
+    if path.exists() {
+        println!("Already have day {} at {}", day, path.display());
+        return Ok(());
+    }
+    let client = Client::new(&site_opts.base_url, Session::load(&site_opts.session_file)?);
+    if let Some(left) = site::time_until_unlock(day) {
+        if !wait {
+            return Err(format!("day {} unlocks in {:?} (use --wait)", day, left).into());
+        }
+        println!("Waiting {:.0?} for day {} to unlock at midnight US Eastern", left, day);
+        std::thread::sleep(left + Duration::from_secs(1));
+    }
+    match site::fetch_input(&client, day, dir)? {
+        Fetched::Cached(path) => println!("Already have day {} at {}", day, path.display()),
+        Fetched::Downloaded(path) => println!("Wrote {}", path.display()),
+    }
+    Ok(())
 }
 
-mod utils;
-mod day01;
-mod day02;
-mod day03;
-mod day04;
-mod day05;
-mod day06;
-mod day07;
-mod day08;
-mod day09;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod day15;
-mod day16;
-mod day17;
-mod day18;
-// mod day19;
-// mod day20;
-// mod day21;
-// mod day22;
-// mod day23;
-// mod day24;
-// mod day25;
-static DAYS: &'static [fn(bool, bool)] = &[
-    day01::day01,
-    day02::day02,
-    day03::day03,
-    day04::day04,
-    day05::day05,
-    day06::day06,
-    day07::day07,
-    day08::day08,
-    day09::day09,
-    day10::day10,
-    day11::day11,
-    day12::day12,
-    day13::day13,
-    day14::day14,
-    day15::day15,
-    day16::day16,
-    day17::day17,
-    day18::day18,
-    // day19::day19,
-    // day20::day20,
-    // day21::day21,
-    // day22::day22,
-    // day23::day23,
-    // day24::day24,
-    // day25::day25,
-];
+// Sends the answer for one part, unless it's already known (right or wrong).
+// Returns whether it was right.
+fn submit(
+    day: u32,
+    part: u32,
+    site_opts: &SiteOpts,
+    source: &InputSource,
+    user: &str,
+    known: &mut AnswerFile,
+) -> Result<bool, Box<dyn Error>> {
+    let (part1, part2) = run_day(find_day(day), source, false, Parts::Only(part))
+        .unwrap_or_else(|err| parse_failed(source, false, err));
+    let computed = part1.or(part2).unwrap();
+    // The site wants a screen's letters.
+    let answer = match &computed {
+        Answer::Screen(rows) => match read_screen(rows) {
+            Some(text) if !text.contains('?') => text,
+            _ => return Err(format!("cannot read the screen:\n{}", rows.join("\n")).into()),
+        },
+        answer => answer.to_string(),
+    };
+    if let Some(expected) = known.expected(day, part) {
+        println!("Day {}.  Part {} is already solved ({}), got {}", day, part, expected, answer);
+        return Ok(known.check(day, part, &computed) == Check::Match);
+    }
 
-fn main() {
-    let opts = Opts::parse();
-    assert!(opts.repeat == 1 || opts.atleast == None);
-    println!("Hello, world!");
-    match opts.day {
-        Some(day) => println!("Day {}", day),
-        None => println!("All days"),
+    println!("Day {}.  Part {}: {}", day, part, answer);
+    let client = Client::new(&site_opts.base_url, Session::load(&site_opts.session_file)?);
+    let mut guesses = Guesses::load(&Guesses::path_for_user(user))?;
+    match submit::submit(&client, &mut guesses, day, part, &answer)? {
+        Verdict::Right => {
+            println!("That's the right answer!");
+            known.record(day, part, &computed);
+            known.save()?;
+            println!("Recorded it in {}", known.path().display());
+            return Ok(true);
+        }
+        Verdict::Wrong(Hint::TooHigh) => println!("Wrong: too high"),
+        Verdict::Wrong(Hint::TooLow) => println!("Wrong: too low"),
+        Verdict::Wrong(Hint::None) => println!("Wrong"),
+        Verdict::Wait(Some(left)) => println!("Answered too recently, wait {:?}", left),
+        Verdict::Wait(None) => println!("Answered too recently"),
+        Verdict::AlreadySolved => println!("Already solved on the site (use --record to save the answer)"),
+        Verdict::Unrecognised(text) => println!("The site said: {}", text),
+    }
+    Ok(false)
+}
+
+// Lints each day's input, printing what's wrong. Returns the number of inputs
+// with problems.
+fn lint_inputs(days: &[&'static dyn DynSolution], source: &InputSource, test_mode: bool) -> usize {
+    let mut bad = 0;
+    for solution in days {
+        let day = solution.day();
+        let raw = if test_mode {
+            solution.example().to_string()
+        } else {
+            match source.load(day) {
+                Ok(raw) => raw,
+                Err(err) => {
+                    say!("Day {:2} | no input: {}: {}", day, source.describe(day), err);
+                    continue;
+                }
+            }
+        };
+        let problems = lint::lint(*solution, &raw);
+        if problems.is_empty() {
+            say!("Day {:2} | ok", day);
+            continue;
+        }
+        bad += 1;
+        let plural = if problems.len() == 1 { "" } else { "s" };
+        say!("Day {:2} | {} problem{} in {}", day, problems.len(), plural, source.describe(day));
+        for problem in problems {
+            say!("  {}", problem.message.replace('\n', "\n  "));
+        }
+    }
+    bad
+}
+
+// Looks up a day, or explains that it isn't implemented yet.
+fn find_day(day: u32) -> &'static dyn DynSolution {
+    match advent21::find_day(day) {
+        Some(solution) => solution,
+        None => {
+            let implemented: Vec<u32> = DAYS.iter().map(|solution| solution.day()).collect();
+            eprintln!("Day {} is not implemented (implemented: {:?})", day, implemented);
+            std::process::exit(2);
+        }
+    }
+}
+
+// Input that can't be parsed stops the run.
+fn parse_failed(source: &InputSource, test_mode: bool, err: ParseError) -> ! {
+    eprintln!("{}", parse_failure(source, test_mode, &err));
+    std::process::exit(1);
+}
+
+// A file the run needs that can't be read or written also stops it.
+fn file_failed(doing: &str, path: &Path, err: Box<dyn Error>) -> ! {
+    eprintln!("Cannot {} {}: {}", doing, path.display(), err);
+    std::process::exit(2);
+}
+
+fn print_answer(day: u32, part: u32, answer: &Option<Answer>) {
+    match answer {
+        None => (),
+        Some(answer @ Answer::Screen(_)) => say!("Day {}.  Part {}:\n{}", day, part, answer),
+        Some(answer) => say!("Day {}.  Part {}: {}", day, part, answer),
+    }
+}
+
+// An answer on one line, for tables.
+fn fmt_short(answer: &Option<Answer>) -> String {
+    match answer {
+        None => "-".to_string(),
+        Some(Answer::Screen(rows)) => format!("({}-row screen)", rows.len()),
+        Some(answer) => answer.to_string(),
+    }
+}
+
+// Time taken by a part, or "-" if it wasn't run.
+fn fmt_part(parts: Parts, part: u32, time: Duration) -> String {
+    if parts.includes(part) {
+        fmt_micros(time)
+    } else {
+        "-".to_string()
+    }
+}
+
+// Checks the answers against the known ones, reporting anything that's off.
+// Returns the number of mismatches.
+fn check_answers(known: &mut AnswerFile, results: &[(u32, Option<Answer>, Option<Answer>)], record: bool) -> usize {
+    let (mut matched, mut mismatched, mut unknown, mut recorded) = (0, 0, 0, 0);
+    for (day, part1, part2) in results {
+        for (part, answer) in [(1, part1), (2, part2)] {
+            let Some(answer) = answer else {
+                continue;
+            };
+            match known.check(*day, part, answer) {
+                Check::Match => matched += 1,
+                Check::Mismatch { expected } => {
+                    mismatched += 1;
+                    say!("Day {}.  Part {}: MISMATCH, got {} but expected {}", day, part, answer, expected);
+                }
+                Check::Unknown => {
+                    unknown += 1;
+                    if record && known.record(*day, part, answer) {
+                        recorded += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    say!("Answers: {} match, {} mismatch, {} unknown", matched, mismatched, unknown);
+    if recorded > 0 {
+        known.save().unwrap_or_else(|err| file_failed("save", known.path(), err));
+        say!("Recorded {} new answers to {}", recorded, known.path().display());
+    }
+    mismatched
+}
+
+// Allocation count, bytes allocated and peak live bytes for each phase, lined
+// up under the timing columns.
+fn print_allocs(parts: Parts, allocs: &PhaseAllocs) {
+    type Getter = fn(&AllocStats) -> usize;
+    let rows: [(&str, Getter); 3] = [
+        ("allocs", |stats| stats.count),
+        ("bytes ", |stats| stats.bytes),
+        ("peak  ", |stats| stats.peak),
+    ];
+    for (label, get) in rows {
+        let cell = |part: u32, stats: &AllocStats| {
+            if parts.includes(part) {
+                get(stats).separate_with_commas()
+            } else {
+                "-".to_string()
+            }
+        };
+        say!(
+            "{} | {:>9} | {:>9} | {:>9} | {:>9} | {:>9} |",
+            label,
+            get(&allocs.total()).separate_with_commas(),
+            get(&allocs.read).separate_with_commas(),
+            get(&allocs.parse).separate_with_commas(),
+            cell(1, &allocs.part1),
+            cell(2, &allocs.part2),
+        );
+    }
+}
+
+// Clap ties options to each other, but not to a subcommand, or to a global
+// option given before one, so those conflicts are reported here the same
+// way.
+fn conflict(message: String) -> ! {
+    Opts::command().error(clap::error::ErrorKind::ArgumentConflict, message).exit()
+}
+
+fn run_new_day(day: u32) {
+    match scaffold::new_day(day) {
+        Ok(paths) => paths.iter().for_each(|path| println!("Wrote {}", path.display())),
+        Err(err) => {
+            eprintln!("Cannot start day {}: {}", day, err);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn run_fetch(day: u32, wait: bool, site_opts: &SiteOpts, dir: &Path) {
+    if let Err(err) = fetch(day, wait, site_opts, dir) {
+        eprintln!("Cannot fetch day {}: {}", day, err);
+        std::process::exit(1);
+    }
+}
+
+fn run_gen(day: u32, scale: u64, example: bool, seed: u64) {
+    let size = if example { gen::Size::Example } else { gen::Size::Full(scale as usize) };
+    match gen::generate(day, size, seed) {
+        Some(input) => print!("{}", input),
+        None => {
+            eprintln!("No generator for day {}", day);
+            std::process::exit(1);
+        }
+    }
+}
+
+// The days picked by the options, or every day.
+fn select_days(opts: &Opts) -> Vec<&'static dyn DynSolution> {
+    say!("Hello, world!");
+    match (opts.day, &opts.days) {
+        (Some(day), _) => {
+            say!("Day {}", day);
+            vec![find_day(day)]
+        }
+        (None, Some(selection)) => {
+            say!("Days {}", selection);
+            let days = selection.filter(DAYS);
+            if days.is_empty() {
+                eprintln!("No implemented days in {}", selection);
+                std::process::exit(2);
+            }
+            days
+        }
+        (None, None) => {
+            say!("All days");
+            DAYS.to_vec()
+        }
+    }
+}
+
+fn input_source(opts: &Opts) -> InputSource {
+    match &opts.input {
+        Some(input) => InputSource::from_arg(input),
+        None => InputSource::Dir(opts.input_dir.clone()),
+    }
+}
+
+// Whose answers the run is checked against.
+fn answers_user(opts: &Opts) -> String {
+    if opts.test {
+        EXAMPLE_USER.to_string()
+    } else {
+        opts.user.clone()
+            .or_else(|| std::env::var("USER").ok())
+            .unwrap_or_else(|| "default".to_string())
+    }
+}
+
+fn load_answers(user: &str) -> AnswerFile {
+    let path = AnswerFile::path_for_user(user);
+    AnswerFile::load(&path).unwrap_or_else(|err| file_failed("load", &path, err))
+}
+
+fn run_lint(opts: &Opts) {
+    let days = select_days(opts);
+    let bad = lint_inputs(&days, &input_source(opts), opts.test);
+    if bad > 0 {
+        say!("{} inputs with problems", bad);
+        std::process::exit(1);
+    }
+}
+
+fn run_submit(opts: &Opts, day: u32, part: u32, site_opts: &SiteOpts) {
+    if opts.test {
+        conflict("-t can't be used with submit, since the examples' answers can't be submitted".to_string());
+    }
+    select_days(opts);
+    let user = answers_user(opts);
+    let mut known = load_answers(&user);
+    match submit(day, part, site_opts, &input_source(opts), &user, &mut known) {
+        Ok(true) => (),
+        Ok(false) => std::process::exit(1),
+        Err(err) => {
+            eprintln!("Cannot submit day {} part {}: {}", day, part, err);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn run_differential(opts: &Opts, seeds: u64, scale: Option<u64>) {
+    let days = select_days(opts);
+    let size = scale.map_or(gen::Size::Example, |scale| gen::Size::Full(scale as usize));
+    let mut failed = false;
+    for solution in &days {
+        match differential::check(*solution, size, 0..seeds) {
+            Ok(0) => say!("Day {:2} | no reference", solution.day()),
+            Ok(agreed) => say!("Day {:2} | {} inputs agree", solution.day(), agreed),
+            Err(disagreement) => {
+                say!("Day {:2} | {}", solution.day(), disagreement);
+                failed = true;
+            }
+        }
+    }
+    if failed {
+        std::process::exit(1);
+    }
+}
+
+fn run_readme(opts: &Opts, path: &Path) {
+    for (given, option) in [(opts.jobs.is_some(), "--jobs"), (opts.input.is_some(), "--input")] {
+        if given {
+            conflict(format!("{} can't be used with readme, which benchmarks one day at a time", option));
+        }
+    }
+    run_days(opts, Some(path));
+}
+
+// Runs the days, either benchmarking them all together or (with `readme`,
+// or any of the per-day options) one at a time, and checks their answers.
+fn run_days(opts: &Opts, readme: Option<&Path>) {
+    let days = select_days(opts);
+    if let Some(root) = &opts.batch {
+        if !batch::run_batch(&days, root) {
+            std::process::exit(1);
+        }
+        return;
     }
+    let user = answers_user(opts);
+    let mut known = load_answers(&user);
+    let source = input_source(opts);
+    let parts = opts.part.map_or(Parts::Both, Parts::Only);
+    let per = opts.per || opts.compare.is_some() || opts.check_budget || readme.is_some();
 
-    if opts.per {
+    let mut regressed = Vec::new();
+    let mut over_budget = 0;
+    let mut timeouts = Vec::new();
+    let timeout = opts.timeout.map(Duration::from_secs_f32);
+    let mut results = Vec::with_capacity(days.len());
+    // Each day's timings, and the time for everything, for --format.
+    let mut timings = BTreeMap::new();
+    let total_time;
+    if per {
         // Benchmarks per-day.
-        let atleast = opts.atleast.unwrap_or(0.5);
-        let mut total = 0f64;
-        for i in 0..DAYS.len() {
-            let started = Instant::now();
-            let mut samples = 0;
-            while started.elapsed().as_secs_f32() < atleast {
-                DAYS[i](opts.test, false);
-                samples += 1;
+        let config = BenchConfig {
+            warmup: Duration::from_secs_f32(opts.warmup),
+            measure: Duration::from_secs_f32(opts.atleast.unwrap_or(0.5)),
+            min_samples: opts.min_samples,
+            max_samples: MAX_SAMPLES,
+        };
+        let mut baseline = Baseline::new();
+        let mut total = Duration::ZERO;
+        say!(
+            "Day    | {:>9} | {:>9} | {:>9} | {:>9} | {:>9} | {:>9} | {:>9} | {:>9} | samples (all in µs)",
+            "median", "read", "parse", "part 1", "part 2", "min", "p95", "σ",
+        );
+        for solution in &days {
+            // A day has to finish once within the timeout before it's benchmarked.
+            if let Some(limit) = timeout {
+                match run_day_limited(*solution, &source, opts.test, parts, limit) {
+                    None => {
+                        say!("Day {:2} | TIMEOUT after {:?}", solution.day(), limit);
+                        timeouts.push(solution.day());
+                        continue;
+                    }
+                    Some(Err(err)) => parse_failed(&source, opts.test, err),
+                    Some(Ok(_)) => (),
+                }
             }
-            let elapsed = started.elapsed();
-            total += (elapsed / samples).as_secs_f64();
-            println!("Day {:2} | {:>7} µs  ({} samples)", i + 1, (elapsed / samples).as_micros().separate_with_commas(), samples);
+
+            let mut answers = None;
+            let (stats, phases) = bench(&config, || {
+                let (day_answers, phases) = run_day_timed(*solution, &source, opts.test, parts)
+                    .unwrap_or_else(|err| parse_failed(&source, opts.test, err));
+                answers = Some(black_box(day_answers));
+                phases
+            });
+            total += stats.median;
+            say!(
+                "Day {:2} | {:>9} | {:>9} | {:>9} | {:>9} | {:>9} | {:>9} | {:>9} | {:>9} | {} ({} outliers)",
+                solution.day(),
+                fmt_micros(stats.median),
+                fmt_micros(phases.read),
+                fmt_micros(phases.parse),
+                fmt_part(parts, 1, phases.part1),
+                fmt_part(parts, 2, phases.part2),
+                fmt_micros(stats.min),
+                fmt_micros(stats.p95),
+                fmt_micros(stats.stddev),
+                stats.samples,
+                stats.outliers,
+            );
+            if opts.alloc_stats {
+                print_allocs(parts, &phases.allocs);
+            }
+
+            timings.insert(solution.day(), Timing::new(&phases, parts, Some(&stats)));
+            baseline.days.insert(solution.day(), stats);
+
+            let (part1, part2) = answers.unwrap();
+            results.push((solution.day(), part1, part2));
+        }
+        say!("Theoretical total: {} ms", total.as_secs_f64() * 1000.0);
+        total_time = total;
+
+        if let Some(path) = &opts.save_baseline {
+            baseline.save(path).unwrap_or_else(|err| file_failed("save", path, err));
+            say!("Saved baseline for {} to {}", baseline.revision, path.display());
+        }
+        if let Some(path) = &opts.compare {
+            let old = Baseline::load(path).unwrap_or_else(|err| file_failed("load", path, err));
+            regressed = baseline.compare(&old, opts.threshold / 100.0);
+        }
+        if let Some(path) = readme {
+            readme::update(path, &baseline.days).unwrap_or_else(|err| file_failed("update", path, err));
+            say!("Updated the timings in {}", path.display());
+        }
+        if opts.check_budget {
+            let budget = Budget::load(&opts.budget).unwrap_or_else(|err| file_failed("load", &opts.budget, err));
+            over_budget = budget.check(&baseline.days);
         }
-        println!("Theoretical total: {} ms", total * 1000.0);
     } else {  // Benchmarks the total
-        // Running one day or everything?
-        let runner: Box<dyn Fn()> = match opts.day {
-            Some(day) => Box::new(move || {
-                DAYS[day as usize - 1](opts.test, opts.repeat == 1 && opts.atleast.is_none());
-            }),
-            None => Box::new(|| {
-                for run_day in DAYS {
-                    run_day(opts.test, false);
-                }
-            })
+        // Time spent on each day, summed over the repeats.
+        let mut day_phases = vec![Phases::default(); days.len()];
+        // Days that ran out of time, which are skipped on later repeats.
+        let mut timed_out = Vec::new();
+        let mut runner = || {
+            // None for the days that timed out.
+            let day_results: Vec<Option<_>> = match (opts.jobs, timeout) {
+                (Some(jobs), _) => run_days_parallel(&days, &source, opts.test, parts, jobs as usize)
+                    .into_iter()
+                    .map(Some)
+                    .collect(),
+                (None, Some(limit)) => days.iter()
+                    .map(|solution| {
+                        if timed_out.contains(&solution.day()) {
+                            return None;
+                        }
+                        run_day_limited(*solution, &source, opts.test, parts, limit)
+                    })
+                    .collect(),
+                (None, None) => days.iter()
+                    .map(|solution| Some(run_day_timed(*solution, &source, opts.test, parts)))
+                    .collect(),
+            };
+            day_results.into_iter()
+                .zip(&days)
+                .zip(&mut day_phases)
+                .map(|((result, solution), phases)| {
+                    let Some(result) = result else {
+                        if !timed_out.contains(&solution.day()) {
+                            timed_out.push(solution.day());
+                        }
+                        return (solution.day(), None, None);
+                    };
+                    let ((part1, part2), run_phases) = result
+                        .unwrap_or_else(|err| parse_failed(&source, opts.test, err));
+                    *phases += run_phases;
+                    (solution.day(), part1, part2)
+                })
+                .collect::<Vec<_>>()
         };
 
         let started = Instant::now();
@@ -115,18 +696,202 @@ fn main() {
         match opts.atleast {
             None =>
                 for _ in 0..opts.repeat {
-                    runner();
+                    results = runner();
                     repeated += 1;
                 },
             Some(atleast) => {
                 while started.elapsed().as_secs_f32() < atleast {
-                    runner();
+                    results = runner();
                     repeated += 1;
                 }
             },
         }
 
         let elapsed = started.elapsed();
-        println!("Took {:?}  ({} samples)", elapsed / repeated, repeated);
+        for day in &timed_out {
+            say!("Day {:2} | TIMEOUT after {:?}", day, timeout.unwrap());
+        }
+        timeouts = timed_out;
+
+        if opts.day.is_some() && opts.repeat == 1 && opts.atleast.is_none() {
+            for (day, part1, part2) in &results {
+                print_answer(*day, 1, part1);
+                print_answer(*day, 2, part2);
+            }
+        }
+        let mut phases = Phases::default();
+        for (solution, day_phases) in days.iter().zip(&day_phases) {
+            phases += *day_phases / repeated;
+            if !timeouts.contains(&solution.day()) {
+                timings.insert(solution.day(), Timing::new(&(*day_phases / repeated), parts, None));
+            }
+        }
+        total_time = elapsed / repeated;
+        if let Some(jobs) = opts.jobs {
+            for ((day, part1, part2), day_phases) in results.iter().zip(&day_phases) {
+                say!(
+                    "Day {:2} | {:>9} µs | {} | {}",
+                    day,
+                    fmt_micros(day_phases.total() / repeated),
+                    fmt_short(part1),
+                    fmt_short(part2),
+                );
+            }
+            let wall = elapsed / repeated;
+            say!(
+                "Took {:?} wall clock, {:?} summed over days  ({:.1}x on {} jobs, {} samples)",
+                wall,
+                phases.total(),
+                phases.total().as_secs_f64() / wall.as_secs_f64(),
+                jobs,
+                repeated,
+            );
+        } else {
+            say!("Took {:?}  ({} samples)", elapsed / repeated, repeated);
+        }
+        say!(
+            "  read {} | parse {} | part 1 {} | part 2 {} (µs)",
+            fmt_micros(phases.read),
+            fmt_micros(phases.parse),
+            fmt_part(parts, 1, phases.part1),
+            fmt_part(parts, 2, phases.part2),
+        );
+
+        if opts.alloc_stats {
+            say!("Allocations per run:");
+            for ((day, _, _), day_phases) in results.iter().zip(&day_phases) {
+                say!(
+                    "Day {:2} | {:>9} | {:>9} | {:>9} | {:>9} | {:>9} |",
+                    day, "total", "read", "parse", "part 1", "part 2",
+                );
+                print_allocs(parts, &(day_phases.allocs / repeated));
+            }
+        }
+    }
+
+    // Made before any answers are recorded, so new ones show as unknown.
+    let report = (opts.format != Format::Text).then(|| Report {
+        mode: if per { "per" } else { "total" },
+        user: user.clone(),
+        days: days.iter()
+            .map(|solution| {
+                let day = solution.day();
+                let answers = results.iter().find(|(result_day, _, _)| *result_day == day);
+                let parts = answers
+                    .map(|(_, part1, part2)| [(1, part1), (2, part2)])
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|(part, answer)| Some(PartReport::new(day, part, answer.as_ref()?, &known)))
+                    .collect();
+                DayReport {
+                    day,
+                    timed_out: timeouts.contains(&day),
+                    parts,
+                    timing_ns: timings.remove(&day),
+                }
+            })
+            .collect(),
+        total_ns: total_time.as_nanos() as u64,
+    });
+
+    let mismatches = check_answers(&mut known, &results, opts.record);
+    if !regressed.is_empty() {
+        say!("Regressed by more than {}%: days {:?}", opts.threshold, regressed);
+    }
+    if !timeouts.is_empty() {
+        say!("Timed out: days {:?}", timeouts);
+        say!("Timings after a timeout are unreliable, since a day that timed out keeps running in the background");
+    }
+    if over_budget > 0 {
+        say!("{} over budget (from {})", over_budget, opts.budget.display());
+    }
+    match (opts.format, &report) {
+        (Format::Json, Some(report)) => println!("{}", report.to_json()),
+        (Format::Csv, Some(report)) => print!("{}", report.to_csv()),
+        _ => (),
+    }
+    if mismatches > 0 || !regressed.is_empty() || over_budget > 0 || !timeouts.is_empty() {
+        std::process::exit(1);
+    }
+}
+
+fn main() {
+    let opts = Opts::parse();
+    if opts.format != Format::Text {
+        out::send_text_to_stderr();
     }
-}
\ No newline at end of file
+    if opts.alloc_stats {
+        alloc::enable();
+    }
+    match &opts.command {
+        Some(Command::NewDay { day }) => run_new_day(*day),
+        Some(Command::Fetch { day, wait, site }) => run_fetch(*day, *wait, site, &opts.input_dir),
+        Some(Command::Gen { day, scale, example, seed }) => run_gen(*day, *scale, *example, *seed),
+        Some(Command::Lint) => run_lint(&opts),
+        Some(Command::Submit { day, part, site }) => run_submit(&opts, *day, *part, site),
+        Some(Command::Differential { seeds, scale }) => run_differential(&opts, *seeds, *scale),
+        Some(Command::Readme { path }) => run_readme(&opts, path),
+        None => run_days(&opts, None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Opts {
+        Opts::try_parse_from(["advent21"].iter().chain(args)).unwrap_or_else(|err| panic!("{:?}: {}", args, err))
+    }
+
+    #[test]
+    fn options_are_consistent() {
+        // Checks every subcommand, not just the ones the other tests parse.
+        Opts::command().debug_assert();
+    }
+
+    #[test]
+    fn fetch_takes_the_input_dir() {
+        let opts = parse(&["fetch", "3", "--input-dir", "/tmp/x"]);
+        assert_eq!(opts.input_dir, PathBuf::from("/tmp/x"));
+    }
+
+    #[test]
+    fn submit_takes_the_user() {
+        let opts = parse(&["submit", "3", "1", "--user", "foo"]);
+        assert_eq!(opts.user.as_deref(), Some("foo"));
+    }
+
+    #[test]
+    fn lint_takes_the_days_and_examples() {
+        let opts = parse(&["lint", "-t"]);
+        assert!(opts.test);
+        let opts = parse(&["lint", "--days", "5-11"]);
+        assert!(opts.days.is_some());
+    }
+
+    #[test]
+    fn differential_takes_the_days() {
+        let opts = parse(&["differential", "--days", "8", "--seeds", "3"]);
+        assert!(opts.days.is_some());
+        assert!(matches!(opts.command, Some(Command::Differential { seeds: 3, scale: None })));
+    }
+
+    #[test]
+    fn input_is_not_an_example() {
+        let err = Opts::try_parse_from(["advent21", "-t", "11", "--input", "file"]).err().unwrap();
+        assert_eq!(err.kind(), clap::error::ErrorKind::ArgumentConflict);
+        assert!(parse(&["11", "--input", "file"]).input.is_some());
+    }
+
+    #[test]
+    fn conflicts_are_caught_by_clap() {
+        for args in [
+            vec!["advent21", "--input", "file"],
+            vec!["advent21", "3", "--input", "file", "--per"],
+            vec!["advent21", "-r", "2", "--atleast", "1"],
+            vec!["advent21", "--jobs", "2", "--compare", "baseline.csv"],
+        ] {
+            assert!(Opts::try_parse_from(&args).is_err(), "{:?}", args);
+        }
+    }
+}
@@ -0,0 +1,81 @@
+// Reads the capital letters drawn on a CRT (day 10): each is 4 pixels wide
+// and 6 tall, with a blank column between letters.
+
+const WIDTH: usize = 4;
+const HEIGHT: usize = 6;
+
+// Every letter seen in puzzles so far, row by row.
+const LETTERS: [(char, [&str; HEIGHT]); 18] = [
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', [".###", "..#.", "..#.", "..#.", "..#.", ".###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Y', ["#...", "#...", ".#.#", "..#.", "..#.", "..#."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+// One letter's worth of a row, which may be cut short.
+fn pixels(row: &str, col: usize) -> &str {
+    row.get(col..(col + WIDTH).min(row.len())).unwrap_or("")
+}
+
+// The text on the screen, with '?' for anything that isn't a known letter.
+// Screens that aren't 6 rows tall can't be read at all.
+pub fn read_screen(rows: &[String]) -> Option<String> {
+    if rows.len() != HEIGHT {
+        return None;
+    }
+    let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+
+    let text = (0..width)
+        .step_by(WIDTH + 1)
+        .map(|col| {
+            LETTERS
+                .iter()
+                .find(|(_, glyph)| glyph.iter().zip(rows).all(|(line, row)| *line == pixels(row, col)))
+                .map_or('?', |(letter, _)| *letter)
+        })
+        .collect();
+    Some(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn screen(text: &str) -> Vec<String> {
+        text.lines().map(String::from).collect()
+    }
+
+    #[test]
+    fn reads_letters() {
+        let rows = screen(
+            "####.####.###..###..###..####.####.####.
+#.......#.#..#.#..#.#..#.#.......#.#....
+###....#..###..#..#.###..###....#..###..
+#.....#...#..#.###..#..#.#.....#...#....
+#....#....#..#.#....#..#.#....#....#....
+#....####.###..#....###..#....####.#....",
+        );
+        assert_eq!(read_screen(&rows).as_deref(), Some("FZBPBFZF"));
+    }
+
+    #[test]
+    fn unknown_letters() {
+        let rows = screen("#...\n.#..\n..#.\n...#\n..#.\n.#..");
+        assert_eq!(read_screen(&rows).as_deref(), Some("?"));
+        assert_eq!(read_screen(&rows[..3]), None);
+    }
+}
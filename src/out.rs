@@ -0,0 +1,25 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// When stdout carries a report for machines (--format json or csv), the text
+// meant for people goes to stderr instead, so it doesn't get in the way.
+static TEXT_TO_STDERR: AtomicBool = AtomicBool::new(false);
+
+pub fn send_text_to_stderr() {
+    TEXT_TO_STDERR.store(true, Ordering::Relaxed);
+}
+
+pub fn text_to_stderr() -> bool {
+    TEXT_TO_STDERR.load(Ordering::Relaxed)
+}
+
+// println! for text meant for people.
+#[macro_export]
+macro_rules! say {
+    ($($arg:tt)*) => {
+        if $crate::out::text_to_stderr() {
+            eprintln!($($arg)*);
+        } else {
+            println!($($arg)*);
+        }
+    };
+}
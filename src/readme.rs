@@ -0,0 +1,95 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::baseline::git_revision;
+use crate::bench::{fmt_micros, Stats};
+
+// The generated table goes between these lines of README.md.
+const START: &str = "<!-- timings: generated by `cargo run --release -- readme` -->";
+const END: &str = "<!-- end of timings -->";
+
+// The CPU's name, as the OS reports it.
+fn cpu_name() -> String {
+    fs::read_to_string("/proc/cpuinfo")
+        .ok()
+        .and_then(|info| {
+            info.lines()
+                .find(|line| line.starts_with("model name"))
+                .and_then(|line| line.split_once(':'))
+                .map(|(_, name)| name.trim().to_string())
+        })
+        .unwrap_or_else(|| format!("unknown {} CPU", std::env::consts::ARCH))
+}
+
+// Year, month and day of a count of days since 1970-01-01.
+fn civil_date(days: i64) -> (i64, u32, u32) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = year_of_era + era * 400 + (month <= 2) as i64;
+    (year, month, day)
+}
+
+// Today's date (UTC), like 2022-12-25.
+fn today() -> String {
+    let secs = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() as i64;
+    let (year, month, day) = civil_date(secs / 86400);
+    format!("{}-{:02}-{:02}", year, month, day)
+}
+
+// The table in the same shape as it's always been, with where and when it was made.
+pub fn timing_table(days: &BTreeMap<u32, Stats>, machine: &str, date: &str, revision: &str) -> String {
+    let mut table = format!("On {}, {} (revision {}):\n```\n", machine, date, revision);
+    let mut total = Duration::ZERO;
+    for (day, stats) in days {
+        table.push_str(&format!("Day {:2} | {:>7} µs  ({} samples)\n", day, fmt_micros(stats.median), stats.samples));
+        total += stats.median;
+    }
+    table.push_str(&format!("Theoretical total: {:.3} ms\n```\n", total.as_secs_f64() * 1000.0));
+    table
+}
+
+// Swaps whatever is between the markers for `section`.
+pub fn replace_section(text: &str, section: &str) -> Result<String, String> {
+    let missing = || format!("no \"{}\" ... \"{}\" section", START, END);
+    let start = text.find(START).ok_or_else(missing)? + START.len();
+    let end = start + text[start..].find(END).ok_or_else(missing)?;
+    Ok(format!("{}\n{}{}", &text[..start], section, &text[end..]))
+}
+
+// Rewrites the timing table in the README with these results.
+pub fn update(path: &Path, days: &BTreeMap<u32, Stats>) -> Result<(), Box<dyn Error>> {
+    let text = fs::read_to_string(path)?;
+    let table = timing_table(days, &cpu_name(), &today(), &git_revision());
+    fs::write(path, replace_section(&text, &table)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dates() {
+        assert_eq!(civil_date(0), (1970, 1, 1));
+        assert_eq!(civil_date(19351), (2022, 12, 25));
+        assert_eq!(civil_date(11016), (2000, 2, 29));
+    }
+
+    #[test]
+    fn replaces_only_the_section() {
+        let readme = format!("Intro\n{}\nold table\n{}\nRules\n", START, END);
+        let updated = replace_section(&readme, "new table\n").unwrap();
+        assert_eq!(updated, format!("Intro\n{}\nnew table\n{}\nRules\n", START, END));
+        assert_eq!(replace_section(&updated, "new table\n").unwrap(), updated);
+        assert!(replace_section("Intro\n", "new table\n").is_err());
+    }
+}
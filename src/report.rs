@@ -0,0 +1,237 @@
+use serde::Serialize;
+
+use crate::answers::{AnswerFile, Check};
+use crate::bench::Stats;
+use crate::ocr::read_screen;
+use crate::runner::Phases;
+use crate::solution::{Answer, Parts};
+
+// A run's answers and timings, for --format json or csv.
+#[derive(Debug, Serialize)]
+pub struct Report {
+    // "per" for the per-day benchmark, or "total".
+    pub mode: &'static str,
+    pub user: String,
+    pub days: Vec<DayReport>,
+    // Summed medians with --per, otherwise the time for everything.
+    pub total_ns: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DayReport {
+    pub day: u32,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub timed_out: bool,
+    pub parts: Vec<PartReport>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing_ns: Option<Timing>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PartReport {
+    pub part: u32,
+    // The text for a screen, which also has its pixel rows.
+    pub answer: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rows: Option<Vec<String>>,
+    // "match", "mismatch" or "unknown".
+    pub status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expected: Option<String>,
+}
+
+// Everything in nanoseconds. Parts that weren't run are left out.
+#[derive(Debug, Serialize)]
+pub struct Timing {
+    pub read: u64,
+    pub parse: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub part1: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub part2: Option<u64>,
+    // Only from benchmarking with --per.
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    pub stats: Option<StatsReport>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StatsReport {
+    pub median: u64,
+    pub min: u64,
+    pub mean: u64,
+    pub p95: u64,
+    pub stddev: u64,
+    pub samples: usize,
+    pub outliers: usize,
+}
+
+fn nanos(d: std::time::Duration) -> u64 {
+    d.as_nanos() as u64
+}
+
+// The letters on a screen, or nothing if it can't be read.
+fn screen_text(rows: &[String]) -> String {
+    read_screen(rows).unwrap_or_default()
+}
+
+impl PartReport {
+    pub fn new(day: u32, part: u32, answer: &Answer, known: &AnswerFile) -> Self {
+        let (text, rows) = match answer {
+            Answer::Screen(rows) => (screen_text(rows), Some(rows.clone())),
+            _ => (answer.to_string(), None),
+        };
+        // A screen is known by its rows, so they're read the same way.
+        let expected = known.expected(day, part).map(|expected| match answer {
+            Answer::Screen(_) => screen_text(&expected.lines().map(String::from).collect::<Vec<_>>()),
+            _ => expected.to_string(),
+        });
+        let status = match known.check(day, part, answer) {
+            Check::Match => "match",
+            Check::Mismatch { .. } => "mismatch",
+            Check::Unknown => "unknown",
+        };
+        PartReport {
+            part,
+            answer: text,
+            rows,
+            status,
+            expected,
+        }
+    }
+}
+
+impl Timing {
+    pub fn new(phases: &Phases, parts: Parts, stats: Option<&Stats>) -> Self {
+        Timing {
+            read: nanos(phases.read),
+            parse: nanos(phases.parse),
+            part1: parts.includes(1).then(|| nanos(phases.part1)),
+            part2: parts.includes(2).then(|| nanos(phases.part2)),
+            stats: stats.map(|stats| StatsReport {
+                median: nanos(stats.median),
+                min: nanos(stats.min),
+                mean: nanos(stats.mean),
+                p95: nanos(stats.p95),
+                stddev: nanos(stats.stddev),
+                samples: stats.samples,
+                outliers: stats.outliers,
+            }),
+        }
+    }
+}
+
+const CSV_HEADER: &str = "day,part,answer,status,expected,read_ns,parse_ns,part_ns,\
+    median_ns,min_ns,mean_ns,p95_ns,stddev_ns,samples,outliers";
+
+// Quoted when it has to be.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+impl Report {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    // One row for each part of each day, repeating the day's timings. The
+    // screen rows don't fit, so that's just the text.
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from(CSV_HEADER);
+        out.push('\n');
+        for day in &self.days {
+            let timing = day.timing_ns.as_ref();
+            let stats = timing.and_then(|timing| timing.stats.as_ref());
+            let num = |value: Option<u64>| value.map_or(String::new(), |value| value.to_string());
+            let stat_cells = [
+                stats.map(|s| s.median),
+                stats.map(|s| s.min),
+                stats.map(|s| s.mean),
+                stats.map(|s| s.p95),
+                stats.map(|s| s.stddev),
+                stats.map(|s| s.samples as u64),
+                stats.map(|s| s.outliers as u64),
+            ]
+            .map(num)
+            .join(",");
+
+            if day.timed_out {
+                out.push_str(&format!("{},,,timeout,,,,,{}\n", day.day, stat_cells));
+            }
+            for part in &day.parts {
+                let part_ns = timing.and_then(|timing| if part.part == 1 { timing.part1 } else { timing.part2 });
+                out.push_str(&format!(
+                    "{},{},{},{},{},{},{},{},{}\n",
+                    day.day,
+                    part.part,
+                    csv_field(&part.answer),
+                    part.status,
+                    csv_field(part.expected.as_deref().unwrap_or("")),
+                    num(timing.map(|timing| timing.read)),
+                    num(timing.map(|timing| timing.parse)),
+                    num(part_ns),
+                    stat_cells,
+                ));
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_quotes_awkward_fields() {
+        assert_eq!(csv_field("12"), "12");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+
+    #[test]
+    fn csv_row_per_part() {
+        let report = Report {
+            mode: "total",
+            user: "example".to_string(),
+            days: vec![
+                DayReport {
+                    day: 1,
+                    timed_out: false,
+                    parts: vec![PartReport {
+                        part: 1,
+                        answer: "24000".to_string(),
+                        rows: None,
+                        status: "match",
+                        expected: Some("24000".to_string()),
+                    }],
+                    timing_ns: Some(Timing { read: 1, parse: 2, part1: Some(3), part2: None, stats: None }),
+                },
+                DayReport { day: 16, timed_out: true, parts: vec![], timing_ns: None },
+            ],
+            total_ns: 6,
+        };
+        let csv = report.to_csv();
+        let rows: Vec<&str> = csv.lines().skip(1).collect();
+        assert_eq!(rows, ["1,1,24000,match,24000,1,2,3,,,,,,,", "16,,,timeout,,,,,,,,,,,"]);
+        assert_eq!(csv.lines().next().unwrap().split(',').count(), rows[0].split(',').count());
+    }
+
+    #[test]
+    fn screens_expect_text() {
+        let glyphs = [["#..#", "#..#", "####", "#..#", "#..#", "#..#"], [".###", "..#.", "..#.", "..#.", "..#.", ".###"]];
+        let rows: Vec<String> = (0..6).map(|row| format!("{}.{}", glyphs[0][row], glyphs[1][row])).collect();
+        let answer = Answer::Screen(rows.clone());
+        let mut known = AnswerFile::load(&std::env::temp_dir().join("advent21-no-such-answers.toml")).unwrap();
+        known.record(10, 2, &answer);
+
+        let report = PartReport::new(10, 2, &answer, &known);
+        assert_eq!(report.answer, "HI");
+        assert_eq!(report.expected.as_deref(), Some("HI"));
+        assert_eq!(report.status, "match");
+        assert_eq!(report.rows, Some(rows));
+    }
+}
@@ -0,0 +1,234 @@
+use std::ops::{AddAssign, Div};
+use std::panic;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+use crate::alloc::{AllocStats, Meter};
+use crate::solution::{Answers, DynSolution, Parts};
+use crate::utils::{load_input, InputSource, ParseError};
+
+// Runs the day's `parts`, on its example in test mode.
+pub fn run_day(
+    solution: &dyn DynSolution,
+    source: &InputSource,
+    test_mode: bool,
+    parts: Parts,
+) -> Result<Answers, ParseError> {
+    if test_mode {
+        solution.solve(solution.example(), true, parts)
+    } else {
+        solution.solve(&load_input(source, solution.day()), false, parts)
+    }
+}
+
+// Time spent in each phase of running a day.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Phases {
+    pub read: Duration,
+    pub parse: Duration,
+    pub part1: Duration,
+    pub part2: Duration,
+    pub allocs: PhaseAllocs,
+}
+
+// Allocations in each phase, when counting them (--alloc-stats).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PhaseAllocs {
+    pub read: AllocStats,
+    pub parse: AllocStats,
+    pub part1: AllocStats,
+    pub part2: AllocStats,
+}
+
+impl PhaseAllocs {
+    pub fn total(&self) -> AllocStats {
+        let mut total = self.read;
+        total += self.parse;
+        total += self.part1;
+        total += self.part2;
+        total
+    }
+}
+
+impl AddAssign for PhaseAllocs {
+    fn add_assign(&mut self, rhs: Self) {
+        self.read += rhs.read;
+        self.parse += rhs.parse;
+        self.part1 += rhs.part1;
+        self.part2 += rhs.part2;
+    }
+}
+
+impl Div<u32> for PhaseAllocs {
+    type Output = PhaseAllocs;
+
+    fn div(self, rhs: u32) -> Self::Output {
+        PhaseAllocs {
+            read: self.read / rhs,
+            parse: self.parse / rhs,
+            part1: self.part1 / rhs,
+            part2: self.part2 / rhs,
+        }
+    }
+}
+
+impl Phases {
+    pub fn total(&self) -> Duration {
+        self.read + self.parse + self.part1 + self.part2
+    }
+}
+
+impl AddAssign for Phases {
+    fn add_assign(&mut self, rhs: Self) {
+        self.read += rhs.read;
+        self.parse += rhs.parse;
+        self.part1 += rhs.part1;
+        self.part2 += rhs.part2;
+        self.allocs += rhs.allocs;
+    }
+}
+
+impl Div<u32> for Phases {
+    type Output = Phases;
+
+    fn div(self, rhs: u32) -> Self::Output {
+        Phases {
+            read: self.read / rhs,
+            parse: self.parse / rhs,
+            part1: self.part1 / rhs,
+            part2: self.part2 / rhs,
+            allocs: self.allocs / rhs,
+        }
+    }
+}
+
+// Like `run_day`, but also times reading the input, parsing and each part.
+pub fn run_day_timed(
+    solution: &dyn DynSolution,
+    source: &InputSource,
+    test_mode: bool,
+    parts: Parts,
+) -> Result<(Answers, Phases), ParseError> {
+    let mut meter = Meter::start();
+    let file_str;
+    let input = if test_mode {
+        solution.example()
+    } else {
+        file_str = load_input(source, solution.day());
+        &file_str
+    };
+    let (read, read_allocs) = meter.lap();
+
+    let (answers, times) = solution.solve_timed(input, test_mode, parts)?;
+    let phases = Phases {
+        read,
+        parse: times.parse,
+        part1: times.part1,
+        part2: times.part2,
+        allocs: PhaseAllocs {
+            read: read_allocs,
+            parse: times.parse_allocs,
+            part1: times.part1_allocs,
+            part2: times.part2_allocs,
+        },
+    };
+    Ok((answers, phases))
+}
+
+// Like `run_day_timed`, but on a worker thread that's given up on after
+// `limit`. Returns None if it timed out. Threads can't be killed, so that one
+// carries on in the background until the program exits, slowing down whatever
+// is timed after it.
+pub fn run_day_limited(
+    solution: &'static dyn DynSolution,
+    source: &InputSource,
+    test_mode: bool,
+    parts: Parts,
+    limit: Duration,
+) -> Option<Result<(Answers, Phases), ParseError>> {
+    let (sender, receiver) = mpsc::channel();
+    let source = source.clone();
+    let worker = thread::Builder::new()
+        .name(format!("day{:02}", solution.day()))
+        .spawn(move || {
+            // Nobody's listening any more if it timed out.
+            let _ = sender.send(run_day_timed(solution, &source, test_mode, parts));
+        })
+        .unwrap();
+
+    match receiver.recv_timeout(limit) {
+        Ok(result) => Some(result),
+        Err(RecvTimeoutError::Timeout) => None,
+        // The worker panicked, so pass that along.
+        Err(RecvTimeoutError::Disconnected) => panic::resume_unwind(worker.join().unwrap_err()),
+    }
+}
+
+// Explains why a day's input couldn't be parsed.
+pub fn parse_failure(source: &InputSource, test_mode: bool, err: &ParseError) -> String {
+    let input = if test_mode {
+        "the example".to_string()
+    } else {
+        source.describe(err.day)
+    };
+    format!("Cannot parse {}, at {}", input, err)
+}
+
+// Runs the days on `jobs` worker threads, each taking the next day whenever
+// it finishes one. Results come back in the same order as `days`.
+pub fn run_days_parallel(
+    days: &[&dyn DynSolution],
+    source: &InputSource,
+    test_mode: bool,
+    parts: Parts,
+    jobs: usize,
+) -> Vec<Result<(Answers, Phases), ParseError>> {
+    let next = AtomicUsize::new(0);
+    let mut results: Vec<_> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..jobs.clamp(1, days.len().max(1)))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut done = Vec::new();
+                    loop {
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(solution) = days.get(i) else {
+                            return done;
+                        };
+                        done.push((i, run_day_timed(*solution, source, test_mode, parts)));
+                    }
+                })
+            })
+            .collect();
+        workers.into_iter().flat_map(|worker| worker.join().unwrap()).collect()
+    });
+    results.sort_by_key(|(i, _)| *i);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DAYS;
+
+    #[test]
+    fn parallel_matches_sequential() {
+        let source = InputSource::Dir("inputs".into());
+        let parallel = run_days_parallel(DAYS, &source, true, Parts::Both, 3);
+        assert_eq!(parallel.len(), DAYS.len());
+        for (solution, result) in DAYS.iter().zip(parallel) {
+            let sequential = run_day(*solution, &source, true, Parts::Both).unwrap();
+            assert_eq!(result.unwrap().0, sequential, "day {}", solution.day());
+        }
+    }
+
+    #[test]
+    fn limited_run_finishes() {
+        let source = InputSource::Dir("inputs".into());
+        let (answers, _) = run_day_limited(DAYS[0], &source, true, Parts::Only(2), Duration::from_secs(60))
+            .unwrap()
+            .unwrap();
+        assert_eq!(answers, (None, Some(45000.into())));
+    }
+}
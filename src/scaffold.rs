@@ -0,0 +1,86 @@
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::answers::{AnswerFile, EXAMPLE_USER};
+
+// A new day that parses its input into lines and answers nothing yet. NN and
+// DAY are filled in.
+const TEMPLATE: &str = r#"use crate::solution::{Answer, Solution};
+use crate::utils::{lines, ParseError};
+
+pub struct DayNN;
+
+impl Solution for DayNN {
+    const DAY: u32 = DAY;
+    const EXAMPLE: &'static str = TEST_EXAMPLE;
+
+    type Parsed = Vec<String>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError> {
+        Ok(lines(input).map(|line| line.rest().to_string()).collect())
+    }
+
+    fn part1(parsed: &Self::Parsed) -> Answer {
+        parsed.len().into()
+    }
+
+    fn part2(_parsed: &Self::Parsed) -> Answer {
+        0.into()
+    }
+}
+
+const TEST_EXAMPLE: &str = "";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::testing::{part1, part2};
+
+    #[test]
+    #[ignore = "needs the example and its answer"]
+    fn example_part1() {
+        assert_eq!(part1::<DayNN>(TEST_EXAMPLE), 0.into());
+    }
+
+    #[test]
+    #[ignore = "needs the example and its answer"]
+    fn example_part2() {
+        assert_eq!(part2::<DayNN>(TEST_EXAMPLE), 0.into());
+    }
+}
+"#;
+
+pub fn source_for(day: u32) -> String {
+    TEMPLATE.replace("NN", &format!("{:02}", day)).replace("DAY;", &format!("{};", day))
+}
+
+// Writes src/dayNN.rs and an empty entry for the day in the example answers.
+// build.rs picks up the new file, so nothing else needs registering. Returns
+// the files it touched.
+pub fn new_day(day: u32) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let path = PathBuf::from(format!("src/day{:02}.rs", day));
+    if path.exists() {
+        return Err(format!("{} already exists", path.display()).into());
+    }
+    fs::write(&path, source_for(day))?;
+
+    let mut answers = AnswerFile::load(&AnswerFile::path_for_user(EXAMPLE_USER))?;
+    answers.add_day(day);
+    answers.save()?;
+    Ok(vec![path, answers.path().to_owned()])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fills_in_the_day() {
+        let source = source_for(7);
+        assert!(source.contains("pub struct Day07;"));
+        assert!(source.contains("const DAY: u32 = 7;"));
+        assert!(source.contains("part1::<Day07>"));
+        assert!(!source.contains("NN"));
+    }
+}
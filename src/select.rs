@@ -0,0 +1,100 @@
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+use crate::solution::DynSolution;
+
+// Which days to run, from a comma-separated list of days and ranges, where
+// a leading "!" excludes instead:
+//
+//   1-10         days 1 through 10
+//   12,15,17     just those
+//   !16          everything except day 16
+//   1-10,!4      days 1 through 10, except 4
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DaySelection {
+    include: Vec<RangeInclusive<u32>>,
+    exclude: Vec<RangeInclusive<u32>>,
+    spec: String,
+}
+
+fn parse_range(item: &str) -> Result<RangeInclusive<u32>, String> {
+    let parse_day = |s: &str| {
+        s.trim().parse::<u32>().map_err(|_| format!("{:?} is not a day number", s.trim()))
+    };
+    let range = match item.split_once('-') {
+        Some((lo, hi)) => parse_day(lo)?..=parse_day(hi)?,
+        None => {
+            let day = parse_day(item)?;
+            day..=day
+        }
+    };
+    if range.is_empty() {
+        return Err(format!("{:?} is an empty range", item));
+    }
+    Ok(range)
+}
+
+impl FromStr for DaySelection {
+    type Err = String;
+
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        let mut selection = DaySelection { include: Vec::new(), exclude: Vec::new(), spec: spec.to_string() };
+        for item in spec.split(',') {
+            let item = item.trim();
+            match item.strip_prefix('!') {
+                Some(excluded) => selection.exclude.push(parse_range(excluded)?),
+                None => selection.include.push(parse_range(item)?),
+            }
+        }
+        Ok(selection)
+    }
+}
+
+impl fmt::Display for DaySelection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.spec)
+    }
+}
+
+impl DaySelection {
+    // With only exclusions, everything else is included.
+    pub fn contains(&self, day: u32) -> bool {
+        let included = self.include.is_empty() || self.include.iter().any(|range| range.contains(&day));
+        included && !self.exclude.iter().any(|range| range.contains(&day))
+    }
+
+    pub fn filter(&self, days: &[&'static dyn DynSolution]) -> Vec<&'static dyn DynSolution> {
+        days.iter().copied().filter(|solution| self.contains(solution.day())).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn selected(spec: &str) -> Vec<u32> {
+        let selection: DaySelection = spec.parse().unwrap();
+        (1..=25).filter(|day| selection.contains(*day)).collect()
+    }
+
+    #[test]
+    fn ranges_and_lists() {
+        assert_eq!(selected("1-4"), [1, 2, 3, 4]);
+        assert_eq!(selected("12,15,17"), [12, 15, 17]);
+        assert_eq!(selected("1-3, 20-21"), [1, 2, 3, 20, 21]);
+    }
+
+    #[test]
+    fn exclusions() {
+        assert_eq!(selected("!2-24"), [1, 25]);
+        assert_eq!(selected("1-5,!2,!4"), [1, 3, 5]);
+    }
+
+    #[test]
+    fn bad_specs() {
+        assert!("".parse::<DaySelection>().is_err());
+        assert!("1-x".parse::<DaySelection>().is_err());
+        assert!("5-3".parse::<DaySelection>().is_err());
+    }
+}
@@ -0,0 +1,216 @@
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub const YEAR: u32 = 2022;
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+// Takes priority over the session file.
+pub const SESSION_VAR: &str = "AOC_SESSION";
+
+// 2022-12-01 00:00 UTC, in seconds since the epoch.
+const DECEMBER_1_UTC: u64 = 1669852800;
+// Puzzles unlock at midnight US Eastern, which is UTC-5 all December.
+const EASTERN_OFFSET: u64 = 5 * 3600;
+
+// The adventofcode.com session cookie. Kept out of Debug output (and
+// everything else) so it doesn't end up in logs.
+pub struct Session(String);
+
+impl fmt::Debug for Session {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Session(..)")
+    }
+}
+
+impl Session {
+    // From $AOC_SESSION, or else the file.
+    pub fn load(file: &Path) -> Result<Self, Box<dyn Error>> {
+        let token = match std::env::var(SESSION_VAR) {
+            Ok(token) => token,
+            Err(_) => fs::read_to_string(file)
+                .map_err(|err| format!("no ${} and cannot read {}: {}", SESSION_VAR, file.display(), err))?,
+        };
+        let token = token.trim();
+        if token.is_empty() {
+            return Err("the session token is empty".into());
+        }
+        Ok(Session(token.to_string()))
+    }
+}
+
+// Talks to adventofcode.com, or whatever stands in for it.
+pub struct Client {
+    base_url: String,
+    session: Session,
+    agent: ureq::Agent,
+}
+
+impl Client {
+    pub fn new(base_url: &str, session: Session) -> Self {
+        let agent = ureq::AgentBuilder::new()
+            .timeout(Duration::from_secs(30))
+            .user_agent(concat!("advent21/", env!("CARGO_PKG_VERSION"), " (ureq)"))
+            .build();
+        Client { base_url: base_url.trim_end_matches('/').to_string(), session, agent }
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}/{}/day/{}", self.base_url, YEAR, path)
+    }
+
+    fn cookie(&self) -> String {
+        format!("session={}", self.session.0)
+    }
+
+    // The page's body, or an error with the start of whatever the site said.
+    fn read(response: Result<ureq::Response, ureq::Error>) -> Result<String, Box<dyn Error>> {
+        match response {
+            Ok(response) => {
+                let mut body = String::new();
+                response.into_reader().take(10 << 20).read_to_string(&mut body)?;
+                Ok(body)
+            }
+            Err(ureq::Error::Status(code, response)) => {
+                let body = response.into_string().unwrap_or_default();
+                let said = body.lines().next().unwrap_or("").trim();
+                Err(format!("the site answered {}: {}", code, said).into())
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    pub fn get(&self, path: &str) -> Result<String, Box<dyn Error>> {
+        Self::read(self.agent.get(&self.url(path)).set("Cookie", &self.cookie()).call())
+    }
+
+    pub fn post(&self, path: &str, form: &[(&str, &str)]) -> Result<String, Box<dyn Error>> {
+        Self::read(self.agent.post(&self.url(path)).set("Cookie", &self.cookie()).send_form(form))
+    }
+}
+
+// When a day's puzzle unlocks.
+pub fn unlock_time(day: u32) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(DECEMBER_1_UTC + (day as u64 - 1) * 86400 + EASTERN_OFFSET)
+}
+
+// How long until the day unlocks, if it hasn't yet.
+pub fn time_until_unlock(day: u32) -> Option<Duration> {
+    unlock_time(day).duration_since(SystemTime::now()).ok()
+}
+
+pub enum Fetched {
+    Cached(PathBuf),
+    Downloaded(PathBuf),
+}
+
+// Downloads a day's input into dir/inputNN.txt, unless it's already there.
+pub fn fetch_input(client: &Client, day: u32, dir: &Path) -> Result<Fetched, Box<dyn Error>> {
+    let path = dir.join(format!("input{:02}.txt", day));
+    if path.exists() {
+        return Ok(Fetched::Cached(path));
+    }
+    let input = client.get(&format!("{}/input", day))?;
+    if input.trim().is_empty() {
+        return Err("the input was empty".into());
+    }
+    fs::create_dir_all(dir)?;
+    fs::write(&path, input)?;
+    Ok(Fetched::Downloaded(path))
+}
+
+// A stand-in for the site, for testing the client.
+#[cfg(test)]
+pub mod testing {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::thread::{self, JoinHandle};
+
+    use super::{Client, Session};
+
+    // Serves one request with this status and body. The handle returns the
+    // request line, headers and body it got.
+    pub fn serve_once(status: &'static str, body: &'static str) -> (Client, JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut request = String::new();
+            let mut length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if let Some(value) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                    length = value.trim().parse().unwrap();
+                }
+                request.push_str(&line);
+                if line == "\r\n" {
+                    break;
+                }
+            }
+            let mut content = vec![0; length];
+            reader.read_exact(&mut content).unwrap();
+            request.push_str(&String::from_utf8(content).unwrap());
+
+            let response = format!(
+                "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+            reader.get_mut().write_all(response.as_bytes()).unwrap();
+            request
+        });
+        (Client::new(&base_url, Session("53cr37".to_string())), server)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::testing::serve_once;
+    use super::*;
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("advent21-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn downloads_then_caches() {
+        let dir = scratch_dir("fetch");
+        let (client, server) = serve_once("200 OK", "1000\n2000\n");
+        assert!(matches!(fetch_input(&client, 3, &dir).unwrap(), Fetched::Downloaded(_)));
+        let request = server.join().unwrap();
+        assert!(request.starts_with("GET /2022/day/3/input "));
+        assert!(request.contains("session=53cr37"));
+        assert_eq!(fs::read_to_string(dir.join("input03.txt")).unwrap(), "1000\n2000\n");
+
+        // Nothing is listening any more, so this only works from the cache.
+        assert!(matches!(fetch_input(&client, 3, &dir).unwrap(), Fetched::Cached(_)));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn failure_says_why_but_not_the_session() {
+        let dir = scratch_dir("fetch-fail");
+        let (client, server) = serve_once("400 Bad Request", "Puzzle inputs differ by user.  Please log in to get your puzzle input.\n");
+        let err = fetch_input(&client, 4, &dir).err().unwrap().to_string();
+        server.join().unwrap();
+        assert!(err.contains("400: Puzzle inputs differ by user."));
+        assert!(!err.contains("53cr37"));
+        assert!(!format!("{:?}", client.session).contains("53cr37"));
+        assert!(!dir.exists());
+    }
+
+    #[test]
+    fn unlocks_at_midnight_eastern() {
+        let secs = |day| unlock_time(day).duration_since(UNIX_EPOCH).unwrap().as_secs();
+        // 2022-12-01 05:00 UTC and 2022-12-25 05:00 UTC.
+        assert_eq!(secs(1), 1669870800);
+        assert_eq!(secs(25), 1671944400);
+    }
+}
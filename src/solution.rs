@@ -0,0 +1,204 @@
+use std::fmt;
+use std::time::Duration;
+
+use crate::alloc::{AllocStats, Meter};
+use crate::utils::ParseError;
+
+// An answer computed by one part of a day.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Answer {
+    Num(i64),
+    Str(String),
+    // Pixels drawn on a CRT (day 10), one '#'/'.' string per row.
+    Screen(Vec<String>),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Num(num) => write!(f, "{}", num),
+            Answer::Str(s) => write!(f, "{}", s),
+            Answer::Screen(rows) => write!(f, "{}", rows.join("\n")),
+        }
+    }
+}
+
+impl From<i32> for Answer {
+    fn from(num: i32) -> Self {
+        Answer::Num(num as i64)
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(num: i64) -> Self {
+        Answer::Num(num)
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(num: usize) -> Self {
+        Answer::Num(num as i64)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Str(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Answer::Str(s.to_string())
+    }
+}
+
+// One day's puzzle. Parsing is split out from the parts so the runner
+// can decide what to run and what to do with the answers.
+pub trait Solution {
+    const DAY: u32;
+    const EXAMPLE: &'static str;
+
+    type Parsed;
+
+    fn parse(input: &str) -> Result<Self::Parsed, ParseError>;
+
+    // Some examples use different puzzle parameters than the real input (day 15).
+    fn parse_example(input: &str) -> Result<Self::Parsed, ParseError> {
+        Self::parse(input)
+    }
+
+    fn part1(parsed: &Self::Parsed) -> Answer;
+    fn part2(parsed: &Self::Parsed) -> Answer;
+
+    // Everything wrong with the input, for `lint`. Days that can carry on past
+    // a bad line report them all, and the rest stop at the first.
+    fn lint(input: &str) -> Vec<ParseError> {
+        Self::parse(input).err().into_iter().collect()
+    }
+
+    // Slow but straightforward versions of the parts, to check the fast ones
+    // against on generated inputs (see `differential`). They panic on input
+    // that breaks the puzzle's promises. None where there isn't one.
+    fn reference1(_parsed: &Self::Parsed) -> Option<Answer> {
+        None
+    }
+
+    fn reference2(_parsed: &Self::Parsed) -> Option<Answer> {
+        None
+    }
+}
+
+// Which parts of a day to run.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Parts {
+    #[default]
+    Both,
+    Only(u32),
+}
+
+impl Parts {
+    pub fn includes(self, part: u32) -> bool {
+        match self {
+            Parts::Both => true,
+            Parts::Only(only) => only == part,
+        }
+    }
+}
+
+// The answers for whichever parts were run.
+pub type Answers = (Option<Answer>, Option<Answer>);
+
+// How long each step of solving took. Parts that weren't run took zero.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SolveTimes {
+    pub parse: Duration,
+    pub part1: Duration,
+    pub part2: Duration,
+    // Only counted with --alloc-stats.
+    pub parse_allocs: AllocStats,
+    pub part1_allocs: AllocStats,
+    pub part2_allocs: AllocStats,
+}
+
+// Object-safe view of a `Solution`, so the days can live in one table.
+pub trait DynSolution: Sync {
+    fn day(&self) -> u32;
+    fn example(&self) -> &'static str;
+    fn solve(&self, input: &str, test_mode: bool, parts: Parts) -> Result<Answers, ParseError>;
+    fn solve_timed(&self, input: &str, test_mode: bool, parts: Parts) -> Result<(Answers, SolveTimes), ParseError>;
+    fn lint(&self, input: &str) -> Vec<ParseError>;
+    // Like `solve`, with the reference versions of the parts.
+    fn solve_reference(&self, input: &str, test_mode: bool, parts: Parts) -> Result<Answers, ParseError>;
+}
+
+fn parse<S: Solution>(input: &str, test_mode: bool) -> Result<S::Parsed, ParseError> {
+    let parsed = if test_mode {
+        S::parse_example(input)
+    } else {
+        S::parse(input)
+    };
+    parsed.map_err(|err| ParseError { day: S::DAY, ..err })
+}
+
+impl<S: Solution + Sync> DynSolution for S {
+    fn day(&self) -> u32 {
+        S::DAY
+    }
+
+    fn example(&self) -> &'static str {
+        S::EXAMPLE
+    }
+
+    fn solve(&self, input: &str, test_mode: bool, parts: Parts) -> Result<Answers, ParseError> {
+        let parsed = parse::<S>(input, test_mode)?;
+        let part1 = parts.includes(1).then(|| S::part1(&parsed));
+        let part2 = parts.includes(2).then(|| S::part2(&parsed));
+        Ok((part1, part2))
+    }
+
+    fn solve_timed(&self, input: &str, test_mode: bool, parts: Parts) -> Result<(Answers, SolveTimes), ParseError> {
+        let mut meter = Meter::start();
+        let parsed = parse::<S>(input, test_mode)?;
+        let (parse, parse_allocs) = meter.lap();
+        let part1 = parts.includes(1).then(|| S::part1(&parsed));
+        let (part1_time, part1_allocs) = meter.lap();
+        let part2 = parts.includes(2).then(|| S::part2(&parsed));
+        let (part2_time, part2_allocs) = meter.lap();
+
+        let times = SolveTimes {
+            parse,
+            part1: part1_time,
+            part2: part2_time,
+            parse_allocs,
+            part1_allocs,
+            part2_allocs,
+        };
+        Ok(((part1, part2), times))
+    }
+
+    fn lint(&self, input: &str) -> Vec<ParseError> {
+        S::lint(input).into_iter().map(|err| ParseError { day: S::DAY, ..err }).collect()
+    }
+
+    fn solve_reference(&self, input: &str, test_mode: bool, parts: Parts) -> Result<Answers, ParseError> {
+        let parsed = parse::<S>(input, test_mode)?;
+        let part1 = parts.includes(1).then(|| S::reference1(&parsed)).flatten();
+        let part2 = parts.includes(2).then(|| S::reference2(&parsed)).flatten();
+        Ok((part1, part2))
+    }
+}
+
+// Helpers for the per-day example tests.
+#[cfg(test)]
+pub mod testing {
+    use super::{Answer, Solution};
+
+    pub fn part1<S: Solution>(example: &str) -> Answer {
+        S::part1(&S::parse_example(example).unwrap())
+    }
+
+    pub fn part2<S: Solution>(example: &str) -> Answer {
+        S::part2(&S::parse_example(example).unwrap())
+    }
+}
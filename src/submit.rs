@@ -0,0 +1,318 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::site::Client;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Hint {
+    TooHigh,
+    TooLow,
+    None,
+}
+
+// What the site made of an answer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Right,
+    Wrong(Hint),
+    // Answered too recently, with how long is left if it said.
+    Wait(Option<Duration>),
+    // That part is already solved.
+    AlreadySolved,
+    // Anything else, as the page's text.
+    Unrecognised(String),
+}
+
+// The text of the page's <article>, without tags and with spaces collapsed.
+fn article_text(html: &str) -> String {
+    let start = html.find("<article").unwrap_or(0);
+    let end = html[start..].find("</article>").map_or(html.len(), |end| start + end);
+    let mut text = String::with_capacity(end - start);
+    let mut in_tag = false;
+    for ch in html[start..end].chars() {
+        match ch {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if !in_tag => text.push(ch),
+            _ => (),
+        }
+    }
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+// "You have 1m 37s left to wait."
+fn parse_wait(text: &str) -> Option<Duration> {
+    let left = text.split("You have ").nth(1)?.split(" left to wait").next()?;
+    let mut secs = 0;
+    for amount in left.split_whitespace() {
+        let split = amount.find(|ch: char| !ch.is_ascii_digit())?;
+        let num: u64 = amount[..split].parse().ok()?;
+        secs += match &amount[split..] {
+            "h" => num * 3600,
+            "m" => num * 60,
+            "s" => num,
+            _ => return None,
+        };
+    }
+    Some(Duration::from_secs(secs))
+}
+
+// "Please wait one minute before trying again", after a wrong answer, or
+// longer after several. A minute if it doesn't say.
+fn wrong_answer_wait(text: &str) -> Duration {
+    let minutes = text
+        .split("wait ")
+        .nth(1)
+        .and_then(|rest| rest.split(" minute").next())
+        .and_then(|amount| if amount == "one" { Some(1) } else { amount.parse().ok() })
+        .unwrap_or(1);
+    Duration::from_secs(60 * minutes)
+}
+
+pub fn parse_response(html: &str) -> Verdict {
+    let text = article_text(html);
+    if text.contains("That's the right answer") {
+        Verdict::Right
+    } else if text.contains("That's not the right answer") {
+        Verdict::Wrong(if text.contains("your answer is too high") {
+            Hint::TooHigh
+        } else if text.contains("your answer is too low") {
+            Hint::TooLow
+        } else {
+            Hint::None
+        })
+    } else if text.contains("You gave an answer too recently") {
+        Verdict::Wait(parse_wait(&text))
+    } else if text.contains("You don't seem to be solving the right level") {
+        Verdict::AlreadySolved
+    } else {
+        Verdict::Unrecognised(text)
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Guess {
+    answer: String,
+    // "too high", "too low" or "wrong".
+    verdict: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct DayGuesses {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    part1: Vec<Guess>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    part2: Vec<Guess>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct GuessFile {
+    // Seconds since the epoch before which the site won't take another answer.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    wait_until: Option<u64>,
+    #[serde(flatten)]
+    days: BTreeMap<String, DayGuesses>,
+}
+
+// Wrong answers already sent for one user, stored next to their answers as
+// `answers/<user>.guesses.toml`, so they're never sent twice.
+pub struct Guesses {
+    path: PathBuf,
+    file: GuessFile,
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs()
+}
+
+impl Guesses {
+    pub fn path_for_user(user: &str) -> PathBuf {
+        PathBuf::from(format!("answers/{}.guesses.toml", user))
+    }
+
+    // A missing file is fine, and just means nothing was guessed yet.
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let file = if path.exists() {
+            toml::from_str(&fs::read_to_string(path)?)?
+        } else {
+            GuessFile::default()
+        };
+        Ok(Self { path: path.to_owned(), file })
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&self.path, toml::to_string_pretty(&self.file)?)?;
+        Ok(())
+    }
+
+    fn part(&self, day: u32, part: u32) -> &[Guess] {
+        match self.file.days.get(&format!("day{:02}", day)) {
+            None => &[],
+            Some(guesses) if part == 1 => &guesses.part1,
+            Some(guesses) => &guesses.part2,
+        }
+    }
+
+    // Why the answer must be wrong, if the earlier guesses already show it.
+    pub fn ruled_out(&self, day: u32, part: u32, answer: &str) -> Option<String> {
+        let num = answer.parse::<i64>().ok();
+        for guess in self.part(day, part) {
+            if guess.answer == answer {
+                return Some(format!("{} was already sent, and was {}", answer, guess.verdict));
+            }
+            let (Some(num), Ok(guessed)) = (num, guess.answer.parse::<i64>()) else {
+                continue;
+            };
+            match guess.verdict.as_str() {
+                "too high" if num >= guessed => return Some(format!("{} was too high", guessed)),
+                "too low" if num <= guessed => return Some(format!("{} was too low", guessed)),
+                _ => (),
+            }
+        }
+        None
+    }
+
+    pub fn record(&mut self, day: u32, part: u32, answer: &str, hint: Hint) {
+        let verdict = match hint {
+            Hint::TooHigh => "too high",
+            Hint::TooLow => "too low",
+            Hint::None => "wrong",
+        };
+        let guesses = self.file.days.entry(format!("day{:02}", day)).or_default();
+        let part = if part == 1 { &mut guesses.part1 } else { &mut guesses.part2 };
+        part.push(Guess { answer: answer.to_string(), verdict: verdict.to_string() });
+    }
+
+    // How long until the site takes answers again, if it said to wait.
+    pub fn wait_left(&self) -> Option<Duration> {
+        let left = self.file.wait_until?.checked_sub(now_secs())?;
+        (left > 0).then(|| Duration::from_secs(left))
+    }
+
+    pub fn wait(&mut self, time: Duration) {
+        self.file.wait_until = Some(now_secs() + time.as_secs());
+    }
+}
+
+// Sends an answer, unless it's already known to be wrong or the site said to
+// wait. Wrong answers and waits are remembered (and saved).
+pub fn submit(client: &Client, guesses: &mut Guesses, day: u32, part: u32, answer: &str) -> Result<Verdict, Box<dyn Error>> {
+    if let Some(reason) = guesses.ruled_out(day, part, answer) {
+        return Err(format!("not sending it: {}", reason).into());
+    }
+    if let Some(left) = guesses.wait_left() {
+        return Err(format!("the site asked to wait another {:?}", left).into());
+    }
+
+    let level = part.to_string();
+    let page = client.post(&format!("{}/answer", day), &[("level", &level), ("answer", answer)])?;
+    let verdict = parse_response(&page);
+    match verdict {
+        // The site won't take another answer for a while after a wrong one either.
+        Verdict::Wrong(hint) => {
+            guesses.record(day, part, answer, hint);
+            guesses.wait(wrong_answer_wait(&article_text(&page)));
+        }
+        // A minute is the shortest wait the site asks for.
+        Verdict::Wait(left) => guesses.wait(left.unwrap_or(Duration::from_secs(60))),
+        _ => return Ok(verdict),
+    }
+    guesses.save()?;
+    Ok(verdict)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::site::testing::serve_once;
+
+    fn page(text: &str) -> String {
+        format!("<html><main>\n<article><p>{}</p></article>\n</main></html>", text)
+    }
+
+    #[test]
+    fn verdicts() {
+        assert_eq!(
+            parse_response(&page("That's the right answer!  You are <span>one gold star</span> closer.")),
+            Verdict::Right
+        );
+        assert_eq!(
+            parse_response(&page("That's not the right answer; your answer is too high.  Please wait one minute.")),
+            Verdict::Wrong(Hint::TooHigh)
+        );
+        assert_eq!(
+            parse_response(&page("That's not the right answer; your answer is too low.")),
+            Verdict::Wrong(Hint::TooLow)
+        );
+        assert_eq!(
+            parse_response(&page("That's not the right answer.  If you're stuck, ...")),
+            Verdict::Wrong(Hint::None)
+        );
+        assert_eq!(
+            parse_response(&page("You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 1m 37s left to wait.")),
+            Verdict::Wait(Some(Duration::from_secs(97)))
+        );
+        assert_eq!(
+            parse_response(&page("You don't seem to be solving the right level.  Did you already complete it?")),
+            Verdict::AlreadySolved
+        );
+        assert_eq!(parse_response("<p>Something new</p>"), Verdict::Unrecognised("Something new".to_string()));
+    }
+
+    #[test]
+    fn wrong_answer_waits() {
+        assert_eq!(wrong_answer_wait("That's not the right answer.  Please wait one minute before trying again."), Duration::from_secs(60));
+        assert_eq!(wrong_answer_wait("That's not the right answer.  Please wait 5 minutes before trying again."), Duration::from_secs(300));
+        assert_eq!(wrong_answer_wait("That's not the right answer."), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn wrong_guesses_rule_out_answers() {
+        let mut guesses = Guesses::load(Path::new("/nonexistent/guesses.toml")).unwrap();
+        guesses.record(1, 1, "500", Hint::TooHigh);
+        guesses.record(1, 1, "100", Hint::TooLow);
+        guesses.record(1, 2, "ABC", Hint::None);
+        assert!(guesses.ruled_out(1, 1, "500").is_some());
+        assert!(guesses.ruled_out(1, 1, "600").is_some());
+        assert!(guesses.ruled_out(1, 1, "50").is_some());
+        assert_eq!(guesses.ruled_out(1, 1, "300"), None);
+        assert!(guesses.ruled_out(1, 2, "ABC").is_some());
+        assert_eq!(guesses.ruled_out(2, 1, "500"), None);
+
+        let saved: GuessFile = toml::from_str(&toml::to_string_pretty(&guesses.file).unwrap()).unwrap();
+        assert_eq!(saved.days["day01"].part1.len(), 2);
+    }
+
+    #[test]
+    fn submits_and_remembers_wrong_answers() {
+        let path = std::env::temp_dir().join(format!("advent21-guesses-{}.toml", std::process::id()));
+        let mut guesses = Guesses::load(&path).unwrap();
+
+        let (client, server) = serve_once(
+            "200 OK",
+            "<article><p>That's not the right answer; your answer is too low.  Please wait one minute before trying again.</p></article>",
+        );
+        assert_eq!(submit(&client, &mut guesses, 7, 2, "1234").unwrap(), Verdict::Wrong(Hint::TooLow));
+        let request = server.join().unwrap();
+        assert!(request.starts_with("POST /2022/day/7/answer "));
+        assert!(request.ends_with("level=2&answer=1234"));
+
+        // Nothing is listening now, so only the remembered guess can answer.
+        let mut reloaded = Guesses::load(&path).unwrap();
+        let err = submit(&client, &mut reloaded, 7, 2, "1000").unwrap_err();
+        assert!(err.to_string().contains("1234 was too low"));
+        // And the wrong answer started the site's minute of waiting.
+        assert!(reloaded.wait_left().unwrap() > Duration::from_secs(55));
+        let err = submit(&client, &mut reloaded, 7, 2, "2000").unwrap_err();
+        assert!(err.to_string().contains("wait"));
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -1,8 +1,291 @@
 use std::{ops::{Index, IndexMut}, cmp::max};
+use std::fmt;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+// Where the puzzle inputs come from.
+#[derive(Clone, Debug)]
+pub enum InputSource {
+    // A directory of inputNN.txt files.
+    Dir(PathBuf),
+    // One file, for running a single day.
+    File(PathBuf),
+    // Standard input, which is read once and reused for every run.
+    Stdin,
+}
+
+impl InputSource {
+    // "-" means stdin.
+    pub fn from_arg(arg: &Path) -> Self {
+        if arg == Path::new("-") {
+            InputSource::Stdin
+        } else {
+            InputSource::File(arg.to_owned())
+        }
+    }
+
+    pub fn is_single(&self) -> bool {
+        !matches!(self, InputSource::Dir(_))
+    }
+
+    pub fn describe(&self, day: u32) -> String {
+        match self {
+            InputSource::Dir(dir) => dir.join(format!("input{:02}.txt", day)).display().to_string(),
+            InputSource::File(path) => path.display().to_string(),
+            InputSource::Stdin => "<stdin>".to_string(),
+        }
+    }
+
+    // Reads the raw input for the day.
+    pub fn load(&self, day: u32) -> std::io::Result<String> {
+        match self {
+            InputSource::Dir(dir) => std::fs::read_to_string(dir.join(format!("input{:02}.txt", day))),
+            InputSource::File(path) => std::fs::read_to_string(path),
+            InputSource::Stdin => {
+                static STDIN: OnceLock<String> = OnceLock::new();
+                if let Some(input) = STDIN.get() {
+                    return Ok(input.clone());
+                }
+                let mut input = String::new();
+                std::io::stdin().read_to_string(&mut input)?;
+                Ok(STDIN.get_or_init(|| input).clone())
+            }
+        }
+    }
+}
+
+// Loads the day's input, without the trailing newline.
+pub fn load_input(source: &InputSource, day: u32) -> String {
+    let mut input = source
+        .load(day)
+        .unwrap_or_else(|err| panic!("Cannot read input {}: {}", source.describe(day), err));
+    input.truncate(input.trim_end().len());
+    input
+}
+
+// Why a day's input couldn't be parsed, and where.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    // Set by the runner, since the parsers don't need to know which day they are.
+    pub day: u32,
+    // Both 1-based, the way an editor counts them.
+    pub line: usize,
+    pub column: usize,
+    pub expected: String,
+    pub found: String,
+    // The offending line, to point at the problem.
+    pub text: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "day {}, line {}, column {}: expected {}, found {}",
+            self.day, self.line, self.column, self.expected, self.found
+        )?;
+        if !self.text.is_empty() {
+            let indent: String = self.text.chars().take(self.column - 1)
+                .map(|ch| if ch == '\t' { '\t' } else { ' ' })
+                .collect();
+            write!(f, "\n    {}\n    {}^", self.text, indent)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+// One line of input, parsed from left to right. Each step either consumes
+// what it expected or returns an error pointing at where it gave up.
+#[derive(Clone, Debug)]
+pub struct Line<'a> {
+    text: &'a str,
+    number: usize,
+    pos: usize,
+}
+
+impl<'a> Line<'a> {
+    pub fn new(number: usize, text: &'a str) -> Self {
+        Line { text, number, pos: 0 }
+    }
+
+    pub fn number(&self) -> usize {
+        self.number
+    }
+
+    // What hasn't been parsed yet.
+    pub fn rest(&self) -> &'a str {
+        &self.text[self.pos..]
+    }
+
+    pub fn at_end(&self) -> bool {
+        self.pos == self.text.len()
+    }
+
+    pub fn error(&self, expected: impl Into<String>) -> ParseError {
+        self.error_at(self.pos, expected)
+    }
+
+    fn error_at(&self, pos: usize, expected: impl Into<String>) -> ParseError {
+        let token: String = self.text[pos..].chars().take_while(|ch| !ch.is_whitespace()).take(20).collect();
+        let found = match self.text[pos..].chars().next() {
+            None => "end of line".to_string(),
+            Some(ch) if ch.is_whitespace() => format!("{:?}", ch),
+            Some(_) => format!("{:?}", token),
+        };
+        ParseError {
+            day: 0,
+            line: self.number,
+            column: self.text[..pos].chars().count() + 1,
+            expected: expected.into(),
+            found,
+            text: self.text.to_string(),
+        }
+    }
+
+    // Skips any leading spaces.
+    pub fn skip_indent(&mut self) {
+        self.pos += self.rest().len() - self.rest().trim_start_matches(' ').len();
+    }
+
+    // Consumes `literal` if it's next.
+    pub fn try_literal(&mut self, literal: &str) -> bool {
+        if self.rest().starts_with(literal) {
+            self.pos += literal.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn literal(&mut self, literal: &str) -> Result<(), ParseError> {
+        if self.try_literal(literal) {
+            Ok(())
+        } else {
+            Err(self.error(format!("{:?}", literal)))
+        }
+    }
+
+    // An optionally negative integer.
+    pub fn integer<T: FromStr>(&mut self) -> Result<T, ParseError> {
+        let rest = self.rest();
+        let sign = usize::from(rest.starts_with('-'));
+        let digits = rest[sign..].bytes().take_while(u8::is_ascii_digit).count();
+        if digits == 0 {
+            return Err(self.error("a number"));
+        }
+        let len = sign + digits;
+        match rest[..len].parse() {
+            Ok(value) => {
+                self.pos += len;
+                Ok(value)
+            }
+            Err(_) => Err(self.error(format!("a number that fits in {}", std::any::type_name::<T>()))),
+        }
+    }
+
+    // A single character accepted by `pred`.
+    pub fn char_where(&mut self, expected: &str, pred: impl Fn(char) -> bool) -> Result<char, ParseError> {
+        match self.rest().chars().next() {
+            Some(ch) if pred(ch) => {
+                self.pos += ch.len_utf8();
+                Ok(ch)
+            }
+            _ => Err(self.error(expected)),
+        }
+    }
+
+    // A run of anything but whitespace and commas, such as a name.
+    pub fn word(&mut self, expected: &str) -> Result<&'a str, ParseError> {
+        let rest = self.rest();
+        let len = rest.find(|ch: char| ch.is_whitespace() || ch == ',').unwrap_or(rest.len());
+        if len == 0 {
+            return Err(self.error(expected));
+        }
+        self.pos += len;
+        Ok(&rest[..len])
+    }
+
+    // Whichever of `choices` comes next.
+    pub fn one_of<T: Copy>(&mut self, choices: &[(&str, T)]) -> Result<T, ParseError> {
+        for (literal, value) in choices {
+            if self.try_literal(literal) {
+                return Ok(*value);
+            }
+        }
+        let names: Vec<String> = choices.iter().map(|(literal, _)| format!("{:?}", literal)).collect();
+        Err(self.error(format!("one of {}", names.join(", "))))
+    }
+
+    // Checks that nothing is left over, such as trailing whitespace.
+    pub fn end(&self) -> Result<(), ParseError> {
+        if self.at_end() {
+            Ok(())
+        } else {
+            Err(self.error("end of line"))
+        }
+    }
+}
+
+// The input's lines, numbered for error messages.
+#[derive(Clone, Debug)]
+pub struct Lines<'a> {
+    lines: std::str::Lines<'a>,
+    number: usize,
+}
+
+pub fn lines(input: &str) -> Lines<'_> {
+    Lines { lines: input.lines(), number: 0 }
+}
+
+// The problem with every line that `parse_line` rejects, rather than just the
+// first, for linting.
+pub fn lint_lines<T>(input: &str, mut parse_line: impl FnMut(Line) -> Result<T, ParseError>) -> Vec<ParseError> {
+    lines(input).filter_map(|line| parse_line(line).err()).collect()
+}
+
+impl<'a> Lines<'a> {
+    // The next line, which has to be there.
+    pub fn next_line(&mut self, expected: &str) -> Result<Line<'a>, ParseError> {
+        match self.next() {
+            Some(line) => Ok(line),
+            None => Err(self.end_of_input(expected)),
+        }
+    }
+
+    pub fn peek(&self) -> Option<Line<'a>> {
+        self.clone().next()
+    }
+
+    // For input that stopped before `expected`.
+    pub fn end_of_input(&self, expected: impl Into<String>) -> ParseError {
+        ParseError {
+            day: 0,
+            line: self.number + 1,
+            column: 1,
+            expected: expected.into(),
+            found: "end of input".to_string(),
+            text: String::new(),
+        }
+    }
+}
+
+impl<'a> Iterator for Lines<'a> {
+    type Item = Line<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let text = self.lines.next()?;
+        self.number += 1;
+        Some(Line::new(self.number, text))
+    }
+}
 
 #[inline]
 pub fn abs_diff(a: usize, b: usize) -> usize {
-    if a > b { a - b } else { b - a }
+    a.abs_diff(b)
 }
 
 pub fn minmax<I>(mut iterable: I) -> Option<(I::Item, I::Item)>
@@ -122,7 +405,7 @@ impl<T> Grid<T> {
     pub fn filled(rows: usize, cols: usize, value: T) -> Grid<T>
         where T: Clone
     {
-        Grid{rows: rows, cols: cols, data: vec![value; rows * cols]}
+        Grid{rows, cols, data: vec![value; rows * cols]}
     }
 
     #[allow(dead_code)]
@@ -344,3 +627,53 @@ impl<T> BorrowPairMut for Vec<T> {
         (part1.get_mut(idx1).unwrap(), part2.first_mut().unwrap())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line_parses_in_order() {
+        let mut line = Line::new(1, "move -3 from 1");
+        line.literal("move ").unwrap();
+        assert_eq!(line.integer::<i32>(), Ok(-3));
+        line.literal(" from ").unwrap();
+        assert_eq!(line.integer::<usize>(), Ok(1));
+        assert!(line.end().is_ok());
+    }
+
+    #[test]
+    fn errors_point_at_the_problem() {
+        let mut line = Line::new(3, "A Y ");
+        line.one_of(&[("A", 0), ("B", 1)]).unwrap();
+        line.literal(" ").unwrap();
+        line.word("a move").unwrap();
+        let err = line.end().unwrap_err();
+        assert_eq!((err.line, err.column), (3, 4));
+        assert_eq!(err.expected, "end of line");
+        assert_eq!(err.found, "' '");
+        assert_eq!(
+            err.to_string(),
+            "day 0, line 3, column 4: expected end of line, found ' '\n    A Y \n       ^"
+        );
+    }
+
+    #[test]
+    fn integer_errors() {
+        let err = Line::new(1, "x5").integer::<i32>().unwrap_err();
+        assert_eq!((err.column, err.expected.as_str(), err.found.as_str()), (1, "a number", "\"x5\""));
+
+        let err = Line::new(1, "300").integer::<u8>().unwrap_err();
+        assert_eq!(err.expected, "a number that fits in u8");
+    }
+
+    #[test]
+    fn lines_report_end_of_input() {
+        let mut lines = lines("a\nb");
+        assert_eq!(lines.next_line("a").unwrap().rest(), "a");
+        assert_eq!(lines.peek().unwrap().number(), 2);
+        lines.next_line("b").unwrap();
+        let err = lines.next_line("c").unwrap_err();
+        assert_eq!((err.line, err.found.as_str()), (3, "end of input"));
+    }
+}